@@ -0,0 +1,53 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::distributions::{Distribution, Uniform};
+
+fn create_input(bits: u32, len: usize) -> Vec<u32> {
+    let max: u64 = (1 << bits) - 1;
+    let between = Uniform::from(0..=max);
+    let mut rng = rand::thread_rng();
+    (0..len).map(|_| between.sample(&mut rng) as u32).collect()
+}
+
+fn bench_encode(c: &mut Criterion) {
+    let input = create_input(16, 10000);
+    c.bench_function("encode 10000 16bit", |b| {
+        b.iter(|| streamvbyte::encode(black_box(&input)))
+    });
+}
+
+fn bench_decode(c: &mut Criterion) {
+    let input = create_input(16, 10000);
+    let encoded = streamvbyte::encode(&input);
+    let mut recovered = vec![0; input.len()];
+    c.bench_function("decode 10000 16bit", |b| {
+        b.iter(|| streamvbyte::decode(black_box(&encoded), &mut recovered))
+    });
+}
+
+#[cfg(feature = "zstd")]
+fn bench_zstd(c: &mut Criterion) {
+    let input = create_input(12, 100000);
+    let raw: Vec<u8> = input.iter().flat_map(|v| v.to_le_bytes()).collect();
+    let vbyte = streamvbyte::encode(&input);
+    let zstd_only = zstd::encode_all(&raw[..], 3).unwrap();
+    let combined = streamvbyte::encode_zstd(&input, 3);
+    println!(
+        "bytes per integer: raw {:.3} vbyte {:.3} zstd {:.3} vbyte+zstd {:.3}",
+        raw.len() as f64 / input.len() as f64,
+        vbyte.len() as f64 / input.len() as f64,
+        zstd_only.len() as f64 / input.len() as f64,
+        combined.len() as f64 / input.len() as f64
+    );
+    c.bench_function("encode_zstd 100000 12bit", |b| {
+        b.iter(|| streamvbyte::encode_zstd(black_box(&input), 3))
+    });
+    c.bench_function("zstd only 100000 12bit", |b| {
+        b.iter(|| zstd::encode_all(black_box(&raw[..]), 3).unwrap())
+    });
+}
+
+#[cfg(not(feature = "zstd"))]
+fn bench_zstd(_: &mut Criterion) {}
+
+criterion_group!(benches, bench_encode, bench_decode, bench_zstd);
+criterion_main!(benches);
@@ -0,0 +1,38 @@
+//! Records the code path the -sys crate compiles the C library with, see `active_backend`.
+
+use std::env;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    let arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
+    let backend = match arch.as_str() {
+        "aarch64" => "neon",
+        "x86" | "x86_64" if c_library_uses_avx() => "sse41",
+        _ => "scalar",
+    };
+    println!("cargo:rustc-env=STREAMVBYTE_BACKEND={}", backend);
+}
+
+/// The C library is built with `-march=native` and selects its x86 SIMD path if AVX is available.
+///
+/// The -sys crate compiles it on the machine running this build script, so a native build checks the
+/// CPU of the build machine. Cross builds fall back to the target features rustc compiles for.
+fn c_library_uses_avx() -> bool {
+    if env::var("HOST").ok() == env::var("TARGET").ok() {
+        return host_has_avx();
+    }
+    env::var("CARGO_CFG_TARGET_FEATURE")
+        .unwrap_or_default()
+        .split(',')
+        .any(|feature| feature == "avx")
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn host_has_avx() -> bool {
+    std::is_x86_feature_detected!("avx")
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn host_has_avx() -> bool {
+    false
+}
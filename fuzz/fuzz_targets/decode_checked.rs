@@ -0,0 +1,25 @@
+//! Decodes arbitrary bytes with an arbitrary count, run with `cargo fuzz run decode_checked`.
+//!
+//! The C library is not instrumented by the sanitizer and its reads past the end of the input usually
+//! stay within the allocation, so this target cannot prove the absence of out of bounds reads. The
+//! guard page tests of the crate cover those deterministically.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use streamvbyte::reference::decode_reference;
+use streamvbyte::try_decode;
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 2 {
+        return;
+    }
+    let count = u16::from_le_bytes([data[0], data[1]]) as usize;
+    let input = &data[2..];
+    let mut output = vec![0; count];
+    if let Ok(bytes_read) = try_decode(input, &mut output) {
+        // accepted input is decoded exactly like the bounds checked pure Rust decoder
+        let mut expected = vec![0; count];
+        assert_eq!(decode_reference(input, &mut expected), bytes_read);
+        assert_eq!(output, expected);
+    }
+});
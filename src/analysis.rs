@@ -0,0 +1,344 @@
+//! Cheap inspection of integer sequences to guide the choice of encoding.
+
+use crate::layout::{code_len, control_bytes_for};
+use crate::reference::value_code;
+#[cfg(feature = "alloc")]
+use crate::zigzag::zigzag_encode_value;
+use crate::{decode_delta_iter, validate_stream, StreamVbyteError};
+
+/// Iterator over the runs of equal consecutive values of a slice, see [`runs`]
+#[derive(Debug, Clone)]
+pub struct Runs<'a> {
+    remaining: &'a [u32],
+}
+
+impl<'a> Iterator for Runs<'a> {
+    type Item = (u32, usize);
+
+    fn next(&mut self) -> Option<(u32, usize)> {
+        let (&value, _) = self.remaining.split_first()?;
+        let run_length = self
+            .remaining
+            .iter()
+            .position(|&v| v != value)
+            .unwrap_or(self.remaining.len());
+        self.remaining = &self.remaining[run_length..];
+        Some((value, run_length))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining.len();
+        (len.min(1), Some(len))
+    }
+}
+
+impl core::iter::FusedIterator for Runs<'_> {}
+
+/// Lazily iterate over the runs of equal consecutive values in `input`.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::runs;
+/// let input = vec![7,7,7,1,2,2];
+/// let found: Vec<(u32,usize)> = runs(&input).collect();
+/// assert_eq!(found,vec![(7,3),(1,1),(2,2)]);
+/// ```
+/// # Return
+///
+/// Returns an iterator yielding `(run_value, run_length)` pairs
+///
+pub fn runs(input: &[u32]) -> Runs<'_> {
+    Runs { remaining: input }
+}
+
+/// Count how many of the `count` integers encoded by [`encode`](crate::encode) use 1, 2, 3 and 4 data bytes.
+///
+/// Only the control section, the first `(count + 3) / 4` bytes of `input`, is read.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{control_byte_histogram,encode};
+/// let out_bytes = encode(&[1,2,44,5123,43,534,1 << 30]);
+/// assert_eq!(control_byte_histogram(&out_bytes,7).unwrap(),[4,2,0,1]);
+/// ```
+/// # Return
+///
+/// Returns the number of integers per data length, the entry `i` counting the integers stored in `i + 1` bytes,
+/// or [`StreamVbyteError::TruncatedStream`] if `input` is shorter than the control section
+///
+pub fn control_byte_histogram(input: &[u8], count: usize) -> Result<[usize; 4], StreamVbyteError> {
+    let control =
+        input
+            .get(..control_bytes_for(count))
+            .ok_or(StreamVbyteError::TruncatedStream(
+                input.len(),
+                control_bytes_for(count),
+            ))?;
+    let mut histogram = [0; 4];
+    for i in 0..count {
+        let code = (control[i / 4] >> (2 * (i % 4))) & 0b11;
+        histogram[code as usize] += 1;
+    }
+    Ok(histogram)
+}
+
+/// Compute the exact number of bytes [`encode`](crate::encode) produces for `input` without encoding it.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode,encoded_len};
+/// let input = vec![1,2,44,5123,43,534];
+/// assert_eq!(encoded_len(&input),encode(&input).len());
+/// ```
+/// # Return
+///
+/// Returns the number of control and data bytes of the encoding
+///
+pub fn encoded_len(input: &[u32]) -> usize {
+    control_bytes_for(input.len())
+        + input
+            .iter()
+            .map(|&v| code_len(value_code(v)))
+            .sum::<usize>()
+}
+
+/// Compute the exact number of bytes [`encode_delta`](crate::encode_delta) produces for `input` and `initial`
+/// without encoding it.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode_delta,encoded_delta_len};
+/// let input = vec![1,2,44,64,71,534];
+/// assert_eq!(encoded_delta_len(&input,1),encode_delta(&input,1).len());
+/// ```
+/// # Return
+///
+/// Returns the number of control and data bytes of the delta encoding
+///
+pub fn encoded_delta_len(input: &[u32], initial: u32) -> usize {
+    let mut prev = initial;
+    control_bytes_for(input.len())
+        + input
+            .iter()
+            .map(|&v| {
+                let gap = v.wrapping_sub(prev);
+                prev = v;
+                code_len(value_code(gap))
+            })
+            .sum::<usize>()
+}
+
+/// The encodings [`recommend_mode`] chooses between
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// [`encode`](crate::encode)
+    Plain,
+    /// [`encode_delta`](crate::encode_delta) with an initial value of `0`
+    Delta,
+    /// [`encode_delta_zigzag`](crate::encode_delta_zigzag)
+    DeltaZigzag,
+}
+
+/// The estimated encoded sizes behind the choice of [`recommend_mode`]
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RecommendationReport {
+    /// The mode with the smallest encoding
+    pub mode: Mode,
+    /// The number of bytes of [`Mode::Plain`]
+    pub plain_len: usize,
+    /// The number of bytes of [`Mode::Delta`], `None` if the input is not non decreasing
+    pub delta_len: Option<usize>,
+    /// The number of bytes of [`Mode::DeltaZigzag`]
+    pub delta_zigzag_len: usize,
+}
+
+/// Compute the encoded size of `input` in every [`Mode`] without encoding it and choose the smallest.
+///
+/// [`Mode::Delta`] is only considered if `input` is non decreasing. Ties are resolved in the order
+/// plain, delta and delta zigzag, preferring the simpler encoding.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{recommendation_report,Mode};
+/// let report = recommendation_report(&[1000,1001,1003,1004,1010,1011]);
+/// assert_eq!(report.mode,Mode::Delta);
+/// assert_eq!(report.delta_len,Some(9));
+/// assert_eq!(report.plain_len,14);
+///
+/// let report = recommendation_report(&[1000,1001,999,1004,1004,1003]);
+/// assert_eq!(report.mode,Mode::DeltaZigzag);
+/// assert_eq!(report.delta_len,None);
+/// ```
+/// # Return
+///
+/// Returns the recommended mode together with the estimated sizes
+///
+#[cfg(feature = "alloc")]
+pub fn recommendation_report(input: &[u32]) -> RecommendationReport {
+    let plain_len = encoded_len(input);
+    let delta_len = input
+        .windows(2)
+        .all(|w| w[0] <= w[1])
+        .then(|| encoded_delta_len(input, 0));
+    let mut prev = 0u32;
+    let delta_zigzag_len = control_bytes_for(input.len())
+        + input
+            .iter()
+            .map(|&v| {
+                let gap = v.wrapping_sub(prev) as i32;
+                prev = v;
+                code_len(value_code(zigzag_encode_value(gap)))
+            })
+            .sum::<usize>();
+    let mut mode = Mode::Plain;
+    let mut best = plain_len;
+    if let Some(len) = delta_len.filter(|&len| len < best) {
+        mode = Mode::Delta;
+        best = len;
+    }
+    if delta_zigzag_len < best {
+        mode = Mode::DeltaZigzag;
+    }
+    RecommendationReport {
+        mode,
+        plain_len,
+        delta_len,
+        delta_zigzag_len,
+    }
+}
+
+/// Choose the [`Mode`] encoding `input` into the fewest bytes, see [`recommendation_report`].
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{recommend_mode,Mode};
+/// assert_eq!(recommend_mode(&[1,2,44,5123,43,534]),Mode::Plain);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn recommend_mode(input: &[u32]) -> Mode {
+    recommendation_report(input).mode
+}
+
+/// Find the smallest and largest of the `count` non decreasing integers encoded by [`encode_delta`](crate::encode_delta).
+///
+/// The smallest integer is the first one, the largest the final prefix sum. Computing it requires a pass
+/// over all values, which are summed up without allocating an output buffer.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{delta_bounds,encode_delta};
+/// let out_bytes = encode_delta(&[10,12,44,64,71,534],3);
+/// assert_eq!(delta_bounds(&out_bytes,6,3).unwrap(),Some((10,534)));
+/// assert_eq!(delta_bounds(&out_bytes,0,3).unwrap(),None);
+/// assert!(delta_bounds(&out_bytes[..5],6,3).is_err());
+/// ```
+/// # Return
+///
+/// Returns the `(min, max)` pair, `None` if `count` is zero, or [`StreamVbyteError::TruncatedStream`] if
+/// `input` is too short to hold `count` integers
+///
+pub fn delta_bounds(
+    input: &[u8],
+    count: usize,
+    initial: u32,
+) -> Result<Option<(u32, u32)>, StreamVbyteError> {
+    validate_stream(input, count)?;
+    let mut values = decode_delta_iter(input, count, initial);
+    Ok(values.next().map(|min| (min, values.last().unwrap_or(min))))
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn runs_cover_input() {
+        let input: Vec<u32> = (0..1000u32).map(|v| (v / 7) % 3 + (v / 100)).collect();
+        let mut expanded = Vec::new();
+        let mut last = None;
+        for (value, run_length) in super::runs(&input) {
+            assert!(run_length > 0);
+            assert_ne!(Some(value), last);
+            last = Some(value);
+            expanded.resize(expanded.len() + run_length, value);
+        }
+        assert_eq!(expanded, input);
+        assert_eq!(super::runs(&[]).next(), None);
+    }
+
+    #[test]
+    fn histogram_counts_data_lengths() {
+        let input = crate::test_input(1001u32);
+        let out_bytes = crate::encode(&input);
+        let mut expected = [0; 4];
+        for &v in &input {
+            expected[crate::reference::value_code(v) as usize] += 1;
+        }
+        assert_eq!(
+            super::control_byte_histogram(&out_bytes, input.len()).unwrap(),
+            expected
+        );
+        assert_eq!(super::control_byte_histogram(&[], 0).unwrap(), [0; 4]);
+        assert!(super::control_byte_histogram(&out_bytes[..250], input.len()).is_err());
+    }
+
+    #[test]
+    fn encoded_len_matches_encode() {
+        for len in [0u32, 1, 5, 1001] {
+            let input = crate::test_input(len);
+            assert_eq!(super::encoded_len(&input), crate::encode(&input).len());
+            assert_eq!(
+                super::encoded_delta_len(&input, 7),
+                crate::encode_delta(&input, 7).len()
+            );
+        }
+    }
+
+    #[test]
+    fn delta_bounds_match_decoded_values() {
+        for len in [1u32, 2, 5, 1001] {
+            let input: Vec<u32> = (0..len).map(|v| 100 + v * 1013 + (v >> 3)).collect();
+            let out_bytes = crate::encode_delta(&input, 50);
+            let bounds = super::delta_bounds(&out_bytes, input.len(), 50).unwrap();
+            assert_eq!(bounds, Some((input[0], input[input.len() - 1])));
+        }
+        let out_bytes = crate::encode_delta(&[1, 2, 3, 70000], 0);
+        assert!(matches!(
+            super::delta_bounds(&out_bytes[..out_bytes.len() - 1], 4, 0),
+            Err(crate::StreamVbyteError::TruncatedStream(..))
+        ));
+    }
+
+    #[test]
+    fn recommendation_matches_encoded_sizes() {
+        let sorted: Vec<u32> = (0..1001u32).map(|v| (1 << 20) + v * 3).collect();
+        let mut jittered = sorted.clone();
+        jittered.swap(10, 11);
+        let random = crate::test_input(1001u32);
+        for (input, mode) in [
+            (&sorted, super::Mode::Delta),
+            (&jittered, super::Mode::DeltaZigzag),
+            (&random, super::Mode::Plain),
+        ] {
+            let report = super::recommendation_report(input);
+            assert_eq!(report.mode, mode);
+            assert_eq!(report.plain_len, crate::encode(input).len());
+            assert_eq!(
+                report.delta_zigzag_len,
+                crate::encode_delta_zigzag(input).len()
+            );
+            if let Some(len) = report.delta_len {
+                assert_eq!(len, crate::encode_delta(input, 0).len());
+            }
+        }
+        assert_eq!(super::recommend_mode(&[]), super::Mode::Plain);
+    }
+}
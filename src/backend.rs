@@ -0,0 +1,20 @@
+//! Introspection of the code path the C library was built with.
+
+/// Returns the name of the code path the C library was compiled with: `"sse41"`, `"neon"` or `"scalar"`.
+///
+/// The -sys crate does not record the configuration it compiled the C library with, so the build script
+/// of this crate derives it the same way the C library does. The C library is built with `-march=native`
+/// and selects its x86 SIMD path if the build machine supports AVX, all aarch64 builds use NEON. The value
+/// is recorded at build time and does not depend on the CPU the binary runs on, so a binary built with AVX
+/// reports `"sse41"` even on a machine where it fails with illegal instructions, see the portability notes
+/// of the crate documentation.
+///
+/// # Examples
+///
+/// ```
+/// let backend = streamvbyte::active_backend();
+/// assert!(["sse41","neon","scalar"].contains(&backend));
+/// ```
+pub fn active_backend() -> &'static str {
+    env!("STREAMVBYTE_BACKEND")
+}
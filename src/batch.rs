@@ -0,0 +1,120 @@
+//! Encoding of many small sequences into a single buffer with an offset table.
+
+use alloc::{vec, vec::Vec};
+use core::convert::TryFrom;
+
+use crate::{encode_onto, validate_stream, Codec};
+
+/// Encode every sequence of `lists` with [`encode`](crate::encode) into one buffer.
+///
+/// The returned offset table holds the position of every encoded list in the buffer followed by the
+/// total length of the buffer, so list `i` is stored in `bytes[offsets[i]..offsets[i + 1]]`. The number of
+/// integers per list is not stored and has to be tracked by the caller.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_batch,encode_batch};
+/// let lists: Vec<&[u32]> = vec![&[1,2,44],&[],&[5123,43,534,7]];
+/// let (bytes,offsets) = encode_batch(&lists);
+/// assert_eq!(offsets,vec![0,4,4,11]);
+/// assert_eq!(decode_batch(&bytes,&offsets,&[3,0,4]),lists);
+/// ```
+/// # Panics
+///
+/// Panics if a list holds more than `u32::MAX` integers or the buffer exceeds `u32::MAX` bytes
+///
+/// # Return
+///
+/// Returns the encoded lists as a byte buffer and the offset table
+///
+pub fn encode_batch(lists: &[&[u32]]) -> (Vec<u8>, Vec<u32>) {
+    let mut bytes = Vec::new();
+    let mut offsets = Vec::with_capacity(lists.len() + 1);
+    offsets.push(0);
+    for list in lists {
+        encode_onto(list, &mut bytes);
+        let offset = u32::try_from(bytes.len()).expect("batches can hold at most u32::MAX bytes");
+        offsets.push(offset);
+    }
+    (bytes, offsets)
+}
+
+/// Decode the lists encoded by [`encode_batch`], list `i` holding `counts[i]` integers.
+///
+/// # Panics
+///
+/// Panics if `offsets` does not hold an offset per count followed by the total length, or a list is too
+/// short to hold its number of integers
+///
+/// # Return
+///
+/// Returns the decoded lists
+///
+pub fn decode_batch(bytes: &[u8], offsets: &[u32], counts: &[usize]) -> Vec<Vec<u32>> {
+    assert_eq!(
+        offsets.len(),
+        counts.len() + 1,
+        "offsets must hold an offset per list followed by the total length"
+    );
+    offsets
+        .windows(2)
+        .zip(counts)
+        .map(|(range, &count)| {
+            let list = &bytes[range[0] as usize..range[1] as usize];
+            assert!(
+                validate_stream(list, count).is_ok(),
+                "list of {} bytes is too short to hold {} integers",
+                list.len(),
+                count
+            );
+            // decoding up to the end of `bytes` only needs a padded copy for the final list
+            let mut output = vec![0; count];
+            // unwrap ok as the list was validated above
+            Codec::Plain
+                .decode_checked(&bytes[range[0] as usize..], &mut output, 0)
+                .unwrap();
+            output
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_roundtrip() {
+        let owned: Vec<Vec<u32>> = (0..100u32)
+            .map(|i| {
+                (0..i % 13)
+                    .map(|v| v.wrapping_mul(2654435761) >> (i % 32))
+                    .collect()
+            })
+            .collect();
+        let lists: Vec<&[u32]> = owned.iter().map(|l| &l[..]).collect();
+        let counts: Vec<usize> = lists.iter().map(|l| l.len()).collect();
+        let (bytes, offsets) = encode_batch(&lists);
+        assert_eq!(offsets.len(), lists.len() + 1);
+        assert_eq!(*offsets.last().unwrap() as usize, bytes.len());
+        for (i, list) in lists.iter().enumerate() {
+            let range = offsets[i] as usize..offsets[i + 1] as usize;
+            assert_eq!(&bytes[range], &crate::encode(list)[..]);
+        }
+        assert_eq!(decode_batch(&bytes, &offsets, &counts), owned);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn batch_of_exactly_sized_bytes() {
+        use crate::guard::GuardedBytes;
+        let owned = [crate::test_input(1001), vec![1; 32]];
+        let lists: Vec<&[u32]> = owned.iter().map(|l| &l[..]).collect();
+        let (bytes, offsets) = encode_batch(&lists);
+        let guarded = GuardedBytes::new(&bytes);
+        assert_eq!(
+            decode_batch(guarded.as_slice(), &offsets, &[1001, 32]),
+            owned
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! Throughput measurements of the codec, for comparing it with other codecs on the host at hand.
+
+use std::time::{Duration, Instant};
+
+use crate::{decode, encode};
+
+/// The minimum time spent repeating a measured operation
+const MIN_DURATION: Duration = Duration::from_millis(100);
+
+/// The outcome of a throughput measurement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchResult {
+    /// The number of integers processed per second
+    pub integers_per_sec: f64,
+    /// The number of encoded bytes per integer
+    pub bytes_per_integer: f64,
+}
+
+/// Create `len` integers drawn uniformly from `0..2^bits`.
+///
+/// The integers are produced by a fixed seed xorshift generator, so every call returns the same input and
+/// measurements are reproducible across runs and hosts.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::bench::create_input;
+/// let input = create_input(12,1000);
+/// assert_eq!(input.len(),1000);
+/// assert!(input.iter().all(|&v| v < 1 << 12));
+/// assert_eq!(input,create_input(12,1000));
+/// ```
+/// # Panics
+///
+/// Panics if `bits` exceeds 32
+///
+pub fn create_input(bits: u32, len: usize) -> Vec<u32> {
+    assert!(bits <= 32, "integers have at most 32 bits");
+    let mask = ((1u64 << bits) - 1) as u32;
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 32) as u32 & mask
+        })
+        .collect()
+}
+
+/// Measure how fast `input` is encoded with [`encode`].
+///
+/// The encoding is repeated for at least 100 milliseconds and the average is reported.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::bench::{create_input,encode_throughput};
+/// let result = encode_throughput(&create_input(16,10000));
+/// println!("{:.0} integers/sec at {:.3} bytes/integer",result.integers_per_sec,result.bytes_per_integer);
+/// ```
+pub fn encode_throughput(input: &[u32]) -> BenchResult {
+    let encoded_len = encode(input).len();
+    let integers_per_sec = measure(input.len(), || {
+        encode(input);
+    });
+    result(input.len(), encoded_len, integers_per_sec)
+}
+
+/// Measure how fast the encoding of `input` is decoded with [`decode`].
+///
+/// The decoding is repeated for at least 100 milliseconds and the average is reported.
+pub fn decode_throughput(input: &[u32]) -> BenchResult {
+    let encoded = encode(input);
+    let mut output = vec![0; input.len()];
+    let integers_per_sec = measure(input.len(), || {
+        decode(&encoded, &mut output);
+    });
+    result(input.len(), encoded.len(), integers_per_sec)
+}
+
+/// Repeat `op` processing `count` integers and return the integers processed per second
+fn measure<F: FnMut()>(count: usize, mut op: F) -> f64 {
+    let start = Instant::now();
+    let mut iterations = 0u64;
+    while iterations == 0 || start.elapsed() < MIN_DURATION {
+        op();
+        iterations += 1;
+    }
+    (count as u64 * iterations) as f64 / start.elapsed().as_secs_f64()
+}
+
+fn result(count: usize, encoded_len: usize, integers_per_sec: f64) -> BenchResult {
+    let bytes_per_integer = if count == 0 {
+        0.0
+    } else {
+        encoded_len as f64 / count as f64
+    };
+    BenchResult {
+        integers_per_sec,
+        bytes_per_integer,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throughput_reports_encoded_size() {
+        let input = create_input(16, 10000);
+        assert!(input.iter().any(|&v| v >= 1 << 8));
+        assert!(create_input(32, 100).iter().any(|&v| v >= 1 << 31));
+        assert!(create_input(0, 100).iter().all(|&v| v == 0));
+
+        let expected = encode(&input).len() as f64 / input.len() as f64;
+        for result in [encode_throughput(&input), decode_throughput(&input)] {
+            assert!(result.integers_per_sec > 0.0);
+            assert_eq!(result.bytes_per_integer, expected);
+        }
+        assert_eq!(encode_throughput(&[]).bytes_per_integer, 0.0);
+    }
+}
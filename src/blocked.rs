@@ -0,0 +1,179 @@
+//! Delta encoded sequences split into independently decodable blocks for random access.
+
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+use crate::{decode_delta, decode_delta_iter, encode_delta};
+
+/// A non decreasing sequence of u32 integers delta encoded in blocks of `block_size` integers, see [`encode_delta_blocked`].
+///
+/// Every block stores its first value as the initial value of its delta encoding, so any position is
+/// reached by decoding at most a single block.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::encode_delta_blocked;
+/// let input: Vec<u32> = (0..1000).map(|v| v * 3).collect();
+/// let blocked = encode_delta_blocked(&input,128);
+/// assert_eq!(blocked.len(),1000);
+/// assert_eq!(blocked.get(500),1500);
+/// assert_eq!(blocked.range(126..130),vec![378,381,384,387]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockedStream {
+    data: Vec<u8>,
+    /// `offsets[i]` is the position of block `i` in `data`, followed by the total length of `data`
+    offsets: Vec<usize>,
+    /// The first value of every block
+    initials: Vec<u32>,
+    block_size: usize,
+    len: usize,
+}
+
+impl BlockedStream {
+    /// The number of encoded integers
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the stream holds no integers
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of integers per block
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// The number of bytes used by the encoded blocks, excluding the block index
+    pub fn encoded_len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// The encoded block `block` with its number of integers
+    fn block(&self, block: usize) -> (&[u8], usize) {
+        let start = block * self.block_size;
+        let count = self.block_size.min(self.len - start);
+        (
+            &self.data[self.offsets[block]..self.offsets[block + 1]],
+            count,
+        )
+    }
+
+    /// Decode the integer at position `index` by decoding the block holding it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not smaller than [`len`](BlockedStream::len)
+    pub fn get(&self, index: usize) -> u32 {
+        assert!(
+            index < self.len,
+            "index {} out of bounds for length {}",
+            index,
+            self.len
+        );
+        let block = index / self.block_size;
+        let (input, count) = self.block(block);
+        decode_delta_iter(input, count, self.initials[block])
+            .nth(index % self.block_size)
+            .unwrap()
+    }
+
+    /// Decode the integers at the positions `range`, only decoding the blocks overlapping the range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is decreasing or ends after [`len`](BlockedStream::len)
+    pub fn range(&self, range: Range<usize>) -> Vec<u32> {
+        assert!(
+            range.start <= range.end && range.end <= self.len,
+            "range {:?} out of bounds for length {}",
+            range,
+            self.len
+        );
+        let mut output = Vec::with_capacity(range.len());
+        if range.is_empty() {
+            return output;
+        }
+        let mut values = vec![0; self.block_size];
+        for block in range.start / self.block_size..=(range.end - 1) / self.block_size {
+            let (input, count) = self.block(block);
+            decode_delta(input, &mut values[..count], self.initials[block]);
+            let block_start = block * self.block_size;
+            let from = range.start.max(block_start) - block_start;
+            let to = range.end.min(block_start + count) - block_start;
+            output.extend_from_slice(&values[from..to]);
+        }
+        output
+    }
+}
+
+/// Encode a sequence of **non decreasing** u32 integers into independently delta encoded blocks of `block_size` integers.
+///
+/// Smaller blocks make [`BlockedStream::get`] and [`BlockedStream::range`] cheaper as fewer integers are
+/// decoded per access, at the cost of a larger block index and an additional zero gap per block. The index
+/// holds an offset and an initial value per block, so blocks of a few hundred integers keep its overhead
+/// below 0.1 bytes per integer.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode_delta,encode_delta_blocked};
+/// let input: Vec<u32> = (0..10000).map(|v| v * 3).collect();
+/// let blocked = encode_delta_blocked(&input,256);
+/// assert!(blocked.encoded_len() < encode_delta(&input,0).len() + 100);
+/// assert_eq!(blocked.range(0..10000),input);
+/// ```
+/// # Panics
+///
+/// Panics if `block_size` is zero
+///
+/// # Return
+///
+/// Returns the encoded blocks with their index
+///
+pub fn encode_delta_blocked(input: &[u32], block_size: usize) -> BlockedStream {
+    assert!(block_size > 0, "block_size must not be zero");
+    let mut data = Vec::new();
+    let mut offsets = vec![0];
+    let mut initials = Vec::with_capacity(input.len().div_ceil(block_size));
+    for block in input.chunks(block_size) {
+        data.extend_from_slice(&encode_delta(block, block[0]));
+        offsets.push(data.len());
+        initials.push(block[0]);
+    }
+    BlockedStream {
+        data,
+        offsets,
+        initials,
+        block_size,
+        len: input.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocked_access_matches_input() {
+        for &block_size in &[1, 3, 4, 100, 2000] {
+            for len in [0u32, 1, 5, 999, 1000] {
+                let input: Vec<u32> = (0..len).map(|v| v * 11 + (v % 7)).collect();
+                let blocked = encode_delta_blocked(&input, block_size);
+                assert_eq!(blocked.len(), input.len());
+                for (i, &expected) in input.iter().enumerate() {
+                    assert_eq!(blocked.get(i), expected);
+                }
+                assert_eq!(blocked.range(0..input.len()), input);
+                for (start, end) in [(0, 0), (1, 4), (97, 405), (500, 999)] {
+                    if end <= input.len() {
+                        assert_eq!(blocked.range(start..end), &input[start..end]);
+                    }
+                }
+            }
+        }
+    }
+}
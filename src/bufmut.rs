@@ -0,0 +1,89 @@
+//! Encoding into the buffers of the [bytes](https://docs.rs/bytes) crate.
+
+use bytes::BufMut;
+
+use crate::{encode, max_compressedbytes};
+
+/// Encode a sequence of u32 integers like [`encode`] and append the result to `out`.
+///
+/// If the current chunk of `out` holds at least `max_compressedbytes(input.len())` bytes the integers
+/// are encoded directly into it without an intermediate copy. Call e.g. `BytesMut::reserve` with
+/// [`max_compressedbytes`] beforehand to guarantee this. Buffers exposing a smaller chunk receive the
+/// output through a temporary buffer with [`BufMut::put_slice`], so non contiguous buffers are supported.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use streamvbyte::{encode,encode_to_bufmut,max_compressedbytes};
+/// let input = vec![1,2,44,5123,43,534];
+/// let mut out_buf = BytesMut::with_capacity(max_compressedbytes(input.len()));
+/// let bytes_written = encode_to_bufmut(&input,&mut out_buf);
+/// assert_eq!(bytes_written,10);
+/// assert_eq!(&out_buf[..],&encode(&input)[..]);
+/// ```
+/// # Panics
+///
+/// Panics if `input` holds more than `u32::MAX` integers or `out` has not enough remaining capacity
+///
+/// # Return
+///
+/// Returns the number of bytes appended to `out`
+///
+pub fn encode_to_bufmut<B: BufMut>(input: &[u32], out: &mut B) -> usize {
+    assert!(
+        input.len() <= u32::MAX as usize,
+        "at most u32::MAX integers can be encoded"
+    );
+    let max_bytes = max_compressedbytes(input.len());
+    let chunk = out.chunk_mut();
+    if chunk.len() >= max_bytes {
+        // the C encoder only writes to the uninitialized chunk, which holds max_compressedbytes bytes
+        let bytes_written = unsafe {
+            streamvbyte_sys::streamvbyte_encode(
+                input.as_ptr(),
+                input.len() as u32,
+                chunk.as_mut_ptr(),
+            ) as usize
+        };
+        unsafe { out.advance_mut(bytes_written) };
+        bytes_written
+    } else {
+        let payload = encode(input);
+        out.put_slice(&payload);
+        payload.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+
+    #[test]
+    fn bufmut_matches_encode() {
+        for len in [0u32, 1, 5, 1000] {
+            let input = crate::test_input(len);
+            let expected = encode(&input);
+
+            let mut reserved = BytesMut::new();
+            reserved.extend_from_slice(b"head");
+            reserved.reserve(max_compressedbytes(input.len()));
+            assert_eq!(encode_to_bufmut(&input, &mut reserved), expected.len());
+            assert_eq!(&reserved[..4], b"head");
+            assert_eq!(&reserved[4..], &expected[..]);
+
+            // an empty Vec reserves 64 bytes in chunk_mut, so only the largest input takes the copying path
+            let mut unreserved: Vec<u8> = Vec::new();
+            assert_eq!(encode_to_bufmut(&input, &mut unreserved), expected.len());
+            assert_eq!(unreserved, expected);
+
+            // a slice of exactly the encoded length is smaller than max_compressedbytes for every non empty input
+            let mut exact = vec![0; expected.len()];
+            let mut window = &mut exact[..];
+            assert_eq!(encode_to_bufmut(&input, &mut window), expected.len());
+            assert!(window.is_empty());
+            assert_eq!(exact, expected);
+        }
+    }
+}
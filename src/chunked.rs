@@ -0,0 +1,118 @@
+//! Encoding of unbounded sequences into independently decodable frames of a fixed number of integers.
+
+use std::io::{self, Write};
+
+use crate::{write_frame, Codec};
+
+/// Encodes integers pushed one at a time into frames of `block_size` integers and writes them to the writer `W`.
+///
+/// Every block is written with [`write_frame`] as soon as it is full, so the output is a sequence of self
+/// describing frames which can be read back one by one with [`read_frame`](crate::read_frame). As every
+/// frame starts with a magic byte and carries its own count, a reader can skip a corrupted frame by
+/// scanning for the next valid frame header.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{read_frame,ChunkedEncoder};
+/// let mut encoder = ChunkedEncoder::new(Vec::new(),4);
+/// for value in &[1,2,44,5123,43,534] {
+///     encoder.push(*value).unwrap();
+/// }
+/// let log = encoder.finish().unwrap();
+/// let mut reader = &log[..];
+/// assert_eq!(read_frame(&mut reader).unwrap().unwrap().1,vec![1,2,44,5123]);
+/// assert_eq!(read_frame(&mut reader).unwrap().unwrap().1,vec![43,534]);
+/// assert!(read_frame(&mut reader).unwrap().is_none());
+/// ```
+#[derive(Debug)]
+pub struct ChunkedEncoder<W: Write> {
+    dst: W,
+    block: Vec<u32>,
+    block_size: usize,
+    blocks: u64,
+}
+
+impl<W: Write> ChunkedEncoder<W> {
+    /// Create a new chunked encoder writing frames of `block_size` integers to `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_size` is zero or exceeds `u32::MAX`
+    pub fn new(dst: W, block_size: usize) -> ChunkedEncoder<W> {
+        assert!(
+            block_size > 0 && block_size <= u32::MAX as usize,
+            "block size must be between 1 and u32::MAX integers"
+        );
+        ChunkedEncoder {
+            dst,
+            block: Vec::with_capacity(block_size),
+            block_size,
+            blocks: 0,
+        }
+    }
+
+    /// Append `value`, writing out the current block as a frame once it holds `block_size` integers
+    pub fn push(&mut self, value: u32) -> io::Result<()> {
+        self.block.push(value);
+        if self.block.len() == self.block_size {
+            self.write_block()?;
+        }
+        Ok(())
+    }
+
+    /// The number of frames written so far
+    pub fn blocks(&self) -> u64 {
+        self.blocks
+    }
+
+    /// Write out the trailing partial block and flush the underlying writer.
+    ///
+    /// Integers of a partial block are lost if the encoder is dropped without calling `finish`.
+    ///
+    /// Returns the underlying writer
+    pub fn finish(mut self) -> io::Result<W> {
+        if !self.block.is_empty() {
+            self.write_block()?;
+        }
+        self.dst.flush()?;
+        Ok(self.dst)
+    }
+
+    fn write_block(&mut self) -> io::Result<()> {
+        write_frame(&mut self.dst, &self.block, Codec::Plain)?;
+        self.block.clear();
+        self.blocks += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_frame;
+
+    #[test]
+    fn chunked_frames_roundtrip() {
+        let input = crate::test_input(1001u32);
+        for block_size in [1, 4, 100, 1001, 5000] {
+            let mut encoder = ChunkedEncoder::new(Vec::new(), block_size);
+            for &value in &input {
+                encoder.push(value).unwrap();
+            }
+            assert_eq!(encoder.blocks(), (input.len() / block_size) as u64);
+            let log = encoder.finish().unwrap();
+
+            let mut reader = &log[..];
+            let mut recovered = Vec::new();
+            while let Some((codec, values)) = read_frame(&mut reader).unwrap() {
+                assert_eq!(codec, Codec::Plain);
+                assert!(values.len() <= block_size);
+                recovered.extend(values);
+            }
+            assert_eq!(recovered, input);
+        }
+        let empty = ChunkedEncoder::new(Vec::new(), 4).finish().unwrap();
+        assert!(empty.is_empty());
+    }
+}
@@ -0,0 +1,282 @@
+//! The different encodings a sequence of u32 integers can be stored in.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::layout::{
+    control_bytes_for, data_len_with, encoded_size, DECODE_SLACK, MAX_BYTES_PER_INT,
+    PLAIN_CODE_LENS, ZERO124_CODE_LENS,
+};
+use crate::zigzag::undo_delta_zigzag;
+use crate::{decode, decode_0124, decode_delta, decode_delta_zigzag, StreamVbyteError};
+
+/// The number of integers [`Codec::decode_padded`] copies to its scratch buffer at a time
+const PADDED_BLOCK: usize = 256;
+#[cfg(feature = "alloc")]
+use crate::{encode, encode_0124, encode_delta, encode_delta_zigzag, Mode};
+
+/// Identifies the encoding of a compressed sequence of u32 integers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Codec {
+    /// The default vbyte encoding produced by [`encode`]
+    Plain,
+    /// Differential coding of a non decreasing sequence produced by [`encode_delta`]
+    Delta,
+    /// The 0124 encoding which stores zeros without data bytes produced by [`encode_0124`]
+    Plain0124,
+    /// Zigzag mapped differences of a nearly sorted sequence produced by [`encode_delta_zigzag`]
+    DeltaZigzag,
+}
+
+// the serialization helpers are only used by the formats built on `std::io`
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+impl Codec {
+    /// The byte used to identify the codec in serialized headers
+    pub(crate) fn tag(self) -> u8 {
+        match self {
+            Codec::Plain => 0,
+            Codec::Delta => 1,
+            Codec::Plain0124 => 2,
+            Codec::DeltaZigzag => 3,
+        }
+    }
+
+    /// Inverse of [`Codec::tag`]
+    pub(crate) fn from_tag(tag: u8) -> Option<Codec> {
+        match tag {
+            0 => Some(Codec::Plain),
+            1 => Some(Codec::Delta),
+            2 => Some(Codec::Plain0124),
+            3 => Some(Codec::DeltaZigzag),
+            _ => None,
+        }
+    }
+
+    /// Encode `input` using this codec. `initial` is ignored by non delta codecs.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn encode(self, input: &[u32], initial: u32) -> Vec<u8> {
+        match self {
+            Codec::Plain => encode(input),
+            Codec::Delta => encode_delta(input, initial),
+            Codec::Plain0124 => encode_0124(input),
+            Codec::DeltaZigzag => encode_delta_zigzag(input),
+        }
+    }
+
+    /// Decode `output.len()` integers using this codec. `initial` is ignored by non delta codecs.
+    pub(crate) fn decode(self, input: &[u8], output: &mut [u32], initial: u32) -> usize {
+        match self {
+            Codec::Plain => decode(input, output),
+            Codec::Delta => decode_delta(input, output, initial),
+            Codec::Plain0124 => decode_0124(input, output),
+            Codec::DeltaZigzag => decode_delta_zigzag(input, output),
+        }
+    }
+
+    /// Validate that `input` holds `output.len()` integers and decode them without reading past the end of `input`.
+    ///
+    /// Every decoding of untrusted input goes through this function. The C decoders read up to
+    /// [`DECODE_SLACK`] bytes past the end of the encoding, so an encoding that ends closer than that to the
+    /// end of `input` is decoded from a padded copy.
+    pub(crate) fn decode_checked(
+        self,
+        input: &[u8],
+        output: &mut [u32],
+        initial: u32,
+    ) -> Result<usize, StreamVbyteError> {
+        let total = self.encoded_size(input, output.len())?;
+        if input.len() - total >= DECODE_SLACK {
+            return Ok(self.decode(input, output, initial));
+        }
+        Ok(self.decode_padded(&input[..total], output, initial))
+    }
+
+    /// Decode the validated encoding `input` block by block from a scratch buffer holding [`DECODE_SLACK`]
+    /// bytes of padding after every block
+    fn decode_padded(self, input: &[u8], output: &mut [u32], initial: u32) -> usize {
+        let mut scratch = [0; PADDED_BLOCK / 4 + PADDED_BLOCK * MAX_BYTES_PER_INT + DECODE_SLACK];
+        let (control, data) = input.split_at(control_bytes_for(output.len()));
+        let mut offset = 0;
+        let mut prev = initial;
+        for (keys, block) in control
+            .chunks(PADDED_BLOCK / 4)
+            .zip(output.chunks_mut(PADDED_BLOCK))
+        {
+            let data_bytes = data_len_with(keys, block.len(), self.code_lens());
+            scratch[..keys.len()].copy_from_slice(keys);
+            scratch[keys.len()..keys.len() + data_bytes]
+                .copy_from_slice(&data[offset..offset + data_bytes]);
+            match self {
+                Codec::Plain | Codec::DeltaZigzag => decode(&scratch, block),
+                Codec::Delta => decode_delta(&scratch, block, prev),
+                Codec::Plain0124 => decode_0124(&scratch, block),
+            };
+            prev = block[block.len() - 1];
+            offset += data_bytes;
+        }
+        if self == Codec::DeltaZigzag {
+            undo_delta_zigzag(output);
+        }
+        control.len() + offset
+    }
+
+    /// The number of data bytes each 2-bit length code occupies in this codec
+    pub(crate) fn code_lens(self) -> &'static [usize; 4] {
+        match self {
+            Codec::Plain | Codec::Delta | Codec::DeltaZigzag => &PLAIN_CODE_LENS,
+            Codec::Plain0124 => &ZERO124_CODE_LENS,
+        }
+    }
+
+    /// Number of bytes of `input` occupied by `count` integers encoded with this codec
+    pub(crate) fn encoded_size(
+        self,
+        input: &[u8],
+        count: usize,
+    ) -> Result<usize, StreamVbyteError> {
+        encoded_size(input, count, self.code_lens())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Mode> for Codec {
+    fn from(mode: Mode) -> Codec {
+        match mode {
+            Mode::Plain => Codec::Plain,
+            Mode::Delta => Codec::Delta,
+            Mode::DeltaZigzag => Codec::DeltaZigzag,
+        }
+    }
+}
+
+/// Encode a sequence of u32 integers with the encoding identified by `codec`.
+///
+/// `initial` is ignored by non delta codecs. Together with [`decode_with`] this allows choosing the
+/// encoding at runtime, e.g. by [`recommend_mode`](crate::recommend_mode), and storing only the codec alongside the data.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_with,encode_with,recommend_mode,Codec};
+/// let input = vec![1000,1001,999,1004,1004,1003];
+/// let codec = Codec::from(recommend_mode(&input));
+/// assert_eq!(codec,Codec::DeltaZigzag);
+/// let out_bytes = encode_with(codec,&input,0);
+/// let mut recovered = vec![0;6];
+/// assert_eq!(decode_with(codec,&out_bytes,&mut recovered,0).unwrap(),out_bytes.len());
+/// assert_eq!(recovered,input);
+/// ```
+/// # Return
+///
+/// Returns the encoded output as a byte buffer
+///
+#[cfg(feature = "alloc")]
+pub fn encode_with(codec: Codec, input: &[u32], initial: u32) -> Vec<u8> {
+    codec.encode(input, initial)
+}
+
+/// Decode `output.len()` u32 integers encoded by [`encode_with`] with the same `codec` and `initial` value.
+///
+/// `initial` is ignored by non delta codecs. `input` is validated before decoding and never read past its
+/// end, so this is safe to call on untrusted input. The i32 encoding of [`encode_zigzag`](crate::encode_zigzag) is not a [`Codec`] as it
+/// decodes into a different type.
+///
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding or
+/// [`StreamVbyteError::TruncatedStream`] if `input` is too short
+///
+pub fn decode_with(
+    codec: Codec,
+    input: &[u8],
+    output: &mut [u32],
+    initial: u32,
+) -> Result<usize, StreamVbyteError> {
+    codec.decode_checked(input, output, initial)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dispatch_matches_codec_functions() {
+        let input: Vec<u32> = (0..1001u32).map(|v| v * 1013 + (v % 7)).collect();
+        for codec in [
+            Codec::Plain,
+            Codec::Delta,
+            Codec::Plain0124,
+            Codec::DeltaZigzag,
+        ] {
+            assert_eq!(Codec::from_tag(codec.tag()), Some(codec));
+            let out_bytes = encode_with(codec, &input, 3);
+            let mut recovered = vec![0; input.len()];
+            assert_eq!(
+                decode_with(codec, &out_bytes, &mut recovered, 3).unwrap(),
+                out_bytes.len()
+            );
+            assert_eq!(recovered, input);
+            assert!(matches!(
+                decode_with(codec, &out_bytes[..out_bytes.len() - 1], &mut recovered, 3),
+                Err(StreamVbyteError::TruncatedStream(..))
+            ));
+        }
+        assert_eq!(Codec::from(Mode::Delta), Codec::Delta);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn decode_with_exactly_sized_input() {
+        let input = crate::test_input(1001);
+        for codec in [
+            Codec::Plain,
+            Codec::Delta,
+            Codec::Plain0124,
+            Codec::DeltaZigzag,
+        ] {
+            let out_bytes = encode_with(codec, &input, 3);
+            let guarded = crate::guard::GuardedBytes::new(&out_bytes);
+            let mut recovered = vec![0; input.len()];
+            decode_with(codec, guarded.as_slice(), &mut recovered, 3).unwrap();
+            assert_eq!(recovered, input);
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn decode_checked_never_reads_past_input() {
+        use crate::guard::GuardedBytes;
+        let input = crate::test_input(1001);
+        for len in [0, 1, 31, 32, 33, 64, 255, 256, 257, 1001] {
+            for codec in [
+                Codec::Plain,
+                Codec::Delta,
+                Codec::Plain0124,
+                Codec::DeltaZigzag,
+            ] {
+                let out_bytes = encode_with(codec, &input[..len], 3);
+                let mut padded = out_bytes.clone();
+                padded.resize(out_bytes.len() + DECODE_SLACK, 0);
+                for bytes in [&out_bytes, &padded] {
+                    // the guard page directly after the buffer faults on any read past its end
+                    let guarded = GuardedBytes::new(bytes);
+                    let mut recovered = vec![0; len];
+                    assert_eq!(
+                        codec
+                            .decode_checked(guarded.as_slice(), &mut recovered, 3)
+                            .unwrap(),
+                        out_bytes.len()
+                    );
+                    assert_eq!(recovered, &input[..len]);
+                }
+            }
+        }
+        let out_bytes = crate::encode(&[1; 32]);
+        let guarded = GuardedBytes::new(&out_bytes);
+        let mut recovered = vec![0; 32];
+        Codec::Plain
+            .decode_checked(guarded.as_slice(), &mut recovered, 0)
+            .unwrap();
+        assert_eq!(recovered, vec![1; 32]);
+    }
+}
@@ -0,0 +1,219 @@
+//! Owned compressed sequences of u32 integers which remember their length and encoding.
+
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "serde")]
+use core::convert::TryFrom;
+
+use crate::{encode, validate_stream, Codec, StreamVbyteError};
+
+/// A sequence of u32 integers stored in the vbyte encoding produced by [`encode`] together with its length.
+///
+/// With the `serde` feature enabled the type serializes as its length `count` and the encoded `bytes`.
+/// Deserialization validates that `bytes` holds a complete encoding of `count` integers.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::CompressedU32s;
+/// let compressed = CompressedU32s::from_slice(&[1,2,44,5123,43,534]);
+/// assert_eq!(compressed.len(),6);
+/// assert_eq!(compressed.as_bytes().len(),10);
+/// assert_eq!(compressed.decode(),vec![1,2,44,5123,43,534]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawCompressedU32s")
+)]
+pub struct CompressedU32s {
+    count: usize,
+    bytes: Vec<u8>,
+}
+
+impl CompressedU32s {
+    /// Compress the integers of `input`
+    pub fn from_slice(input: &[u32]) -> CompressedU32s {
+        CompressedU32s {
+            count: input.len(),
+            bytes: encode(input),
+        }
+    }
+
+    /// Wrap the vbyte encoding of `count` integers, validating that `bytes` holds all of them
+    pub fn from_bytes(bytes: Vec<u8>, count: usize) -> Result<CompressedU32s, StreamVbyteError> {
+        validate_stream(&bytes, count)?;
+        Ok(CompressedU32s { count, bytes })
+    }
+
+    /// Decode all integers into a new buffer
+    pub fn decode(&self) -> Vec<u32> {
+        let mut output = vec![0; self.count];
+        // unwrap ok as the bytes were validated on construction
+        Codec::Plain
+            .decode_checked(&self.bytes, &mut output, 0)
+            .unwrap();
+        output
+    }
+
+    /// The number of compressed integers
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if no integers are stored
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// The vbyte encoded bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// A sequence of u32 integers compressed with any [`Codec`] together with its length and initial value.
+///
+/// The buffer remembers how it was encoded, so it is always decoded with the matching codec.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{Codec,CompressedBuffer};
+/// let input = vec![1000,1001,1002,5000,5001,5002];
+/// let compressed = CompressedBuffer::encode_with(&input,Codec::Delta,0);
+/// assert_eq!(compressed.len(),6);
+/// assert_eq!(compressed.codec(),Codec::Delta);
+/// assert!(compressed.byte_len() < CompressedBuffer::encode(&input).byte_len());
+/// assert_eq!(compressed.decode(),input);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompressedBuffer {
+    codec: Codec,
+    initial: u32,
+    count: usize,
+    bytes: Vec<u8>,
+}
+
+impl CompressedBuffer {
+    /// Compress the integers of `input` with [`Codec::Plain`]
+    pub fn encode(input: &[u32]) -> CompressedBuffer {
+        CompressedBuffer::encode_with(input, Codec::Plain, 0)
+    }
+
+    /// Compress the integers of `input` with `codec`. `initial` is ignored by non delta codecs.
+    pub fn encode_with(input: &[u32], codec: Codec, initial: u32) -> CompressedBuffer {
+        CompressedBuffer {
+            codec,
+            initial,
+            count: input.len(),
+            bytes: codec.encode(input, initial),
+        }
+    }
+
+    /// Decode all integers into a new buffer
+    pub fn decode(&self) -> Vec<u32> {
+        let mut output = vec![0; self.count];
+        self.decode_into(&mut output);
+        output
+    }
+
+    /// Decode all integers into `output`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `output.len()` is not [`len`](CompressedBuffer::len)
+    pub fn decode_into(&self, output: &mut [u32]) {
+        assert_eq!(
+            output.len(),
+            self.count,
+            "output len does not match the number of compressed integers"
+        );
+        // unwrap ok as the bytes were produced by the codec for `count` integers
+        self.codec
+            .decode_checked(&self.bytes, output, self.initial)
+            .unwrap();
+    }
+
+    /// The number of compressed integers
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if no integers are stored
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// The number of encoded bytes
+    pub fn byte_len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// The codec the integers are encoded with
+    pub fn codec(&self) -> Codec {
+        self.codec
+    }
+
+    /// The encoded bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// The unvalidated serialized form of [`CompressedU32s`]
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawCompressedU32s {
+    count: usize,
+    bytes: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawCompressedU32s> for CompressedU32s {
+    type Error = StreamVbyteError;
+
+    fn try_from(raw: RawCompressedU32s) -> Result<CompressedU32s, StreamVbyteError> {
+        CompressedU32s::from_bytes(raw.bytes, raw.count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compressed_buffer_decodes_with_its_codec() {
+        let input: Vec<u32> = (0..1001)
+            .map(|v| if v % 3 == 0 { v * 7 } else { v * 7 + 1 })
+            .collect();
+        for codec in [
+            Codec::Plain,
+            Codec::Delta,
+            Codec::Plain0124,
+            Codec::DeltaZigzag,
+        ] {
+            let compressed = CompressedBuffer::encode_with(&input, codec, 0);
+            assert_eq!(compressed.len(), input.len());
+            assert_eq!(compressed.as_bytes(), &codec.encode(&input, 0)[..]);
+            assert_eq!(compressed.decode(), input);
+        }
+        assert!(CompressedBuffer::encode(&[]).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_roundtrip_validates_length() {
+        let input: Vec<u32> = (0..1000).map(|v| v * 997).collect();
+        let compressed = CompressedU32s::from_slice(&input);
+        let json = serde_json::to_string(&compressed).unwrap();
+        let recovered: CompressedU32s = serde_json::from_str(&json).unwrap();
+        assert_eq!(recovered, compressed);
+        assert_eq!(recovered.decode(), input);
+
+        let truncated =
+            serde_json::json!({ "count": 1000, "bytes": &compressed.as_bytes()[..100] });
+        let err = serde_json::from_value::<CompressedU32s>(truncated).unwrap_err();
+        assert!(err.to_string().contains("truncated"));
+    }
+}
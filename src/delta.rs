@@ -0,0 +1,154 @@
+//! In place conversions between non decreasing sequences and their gaps, the building blocks of the delta encoding.
+//!
+//! Both conversions use wrapping arithmetic like the C library, so applying one after the other restores
+//! any input, sorted or not. [`decode_delta_mode`] allows detecting prefix sums which wrap around instead.
+
+use crate::{decode_delta, Codec, StreamVbyteError};
+
+/// How [`decode_delta_mode`] treats prefix sums exceeding `u32::MAX`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeltaMode {
+    /// Prefix sums wrap around like in [`decode_delta`]
+    Wrapping,
+    /// The input is validated and prefix sums exceeding `u32::MAX` are reported as an error
+    Checked,
+}
+
+/// Replace the gaps in `values` by their running sum starting at `initial`, turning the gaps stored by
+/// [`encode_delta`](crate::encode_delta) back into the original sequence.
+///
+/// Sums exceeding `u32::MAX` wrap around.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::delta_prefix_sum_in_place;
+/// let mut values = vec![1,1,42,20,7,463];
+/// delta_prefix_sum_in_place(&mut values,0);
+/// assert_eq!(values,vec![1,2,44,64,71,534]);
+/// ```
+pub fn delta_prefix_sum_in_place(values: &mut [u32], initial: u32) {
+    let mut prev = initial;
+    for value in values.iter_mut() {
+        prev = prev.wrapping_add(*value);
+        *value = prev;
+    }
+}
+
+/// Replace every value by its difference to its predecessor, the first value by its difference to `initial`.
+///
+/// This is the inverse of [`delta_prefix_sum_in_place`]. Differences of decreasing values wrap around.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::delta_differences_in_place;
+/// let mut values = vec![1,2,44,64,71,534];
+/// delta_differences_in_place(&mut values,0);
+/// assert_eq!(values,vec![1,1,42,20,7,463]);
+/// ```
+pub fn delta_differences_in_place(values: &mut [u32], initial: u32) {
+    let mut prev = initial;
+    for value in values.iter_mut() {
+        let current = *value;
+        *value = current.wrapping_sub(prev);
+        prev = current;
+    }
+}
+
+/// Decode a sequence of non decreasing u32 integers like [`decode_delta`], treating overflowing prefix sums according to `mode`.
+///
+/// A stream produced by [`encode_delta`](crate::encode_delta) from a non decreasing input never overflows,
+/// so [`DeltaMode::Checked`] detects corrupted input which would otherwise decode into a decreasing sequence.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_delta_mode,encode,encode_delta,DeltaMode,StreamVbyteError};
+/// let mut recovered = vec![0;3];
+/// let out_buf = encode_delta(&[1,2,44],0);
+/// assert_eq!(decode_delta_mode(&out_buf,&mut recovered,0,DeltaMode::Checked).unwrap(),out_buf.len());
+///
+/// // gaps of u32::MAX wrap around at the second value
+/// let overflowing = encode(&[1,u32::MAX,0]);
+/// assert!(decode_delta_mode(&overflowing,&mut recovered,0,DeltaMode::Wrapping).is_ok());
+/// assert!(matches!(
+///     decode_delta_mode(&overflowing,&mut recovered,0,DeltaMode::Checked),
+///     Err(StreamVbyteError::DeltaOverflow { index: 1 })
+/// ));
+/// ```
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding. With [`DeltaMode::Checked`]
+/// [`StreamVbyteError::TruncatedStream`] is returned if `input` is too short and [`StreamVbyteError::DeltaOverflow`]
+/// with the index of the first overflowing prefix sum. `output` holds the wrapped values in that case.
+///
+pub fn decode_delta_mode(
+    input: &[u8],
+    output: &mut [u32],
+    initial: u32,
+    mode: DeltaMode,
+) -> Result<usize, StreamVbyteError> {
+    if mode == DeltaMode::Wrapping {
+        return Ok(decode_delta(input, output, initial));
+    }
+    let bytes_read = Codec::Delta.decode_checked(input, output, initial)?;
+    // adding a gap of at most u32::MAX wraps around exactly if the sum is smaller than its predecessor
+    let mut prev = initial;
+    for (index, &value) in output.iter().enumerate() {
+        if value < prev {
+            return Err(StreamVbyteError::DeltaOverflow { index });
+        }
+        prev = value;
+    }
+    Ok(bytes_read)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_sums_invert_differences_at_the_boundary() {
+        let input = vec![u32::MAX - 2, u32::MAX - 1, u32::MAX, u32::MAX, 0, 5];
+        let mut values = input.clone();
+        delta_differences_in_place(&mut values, u32::MAX - 3);
+        assert_eq!(values, vec![1, 1, 1, 0, 1, 5]);
+        assert_eq!(
+            values,
+            crate::decode_to_vec(&crate::encode_delta(&input, u32::MAX - 3), input.len())
+        );
+        delta_prefix_sum_in_place(&mut values, u32::MAX - 3);
+        assert_eq!(values, input);
+
+        let mut empty: [u32; 0] = [];
+        delta_prefix_sum_in_place(&mut empty, 7);
+        delta_differences_in_place(&mut empty, 7);
+    }
+
+    #[test]
+    fn checked_mode_detects_overflow() {
+        let mut output = [0; 4];
+        let gaps = crate::encode(&[5, 10, u32::MAX - 20, 5]);
+        assert!(decode_delta_mode(&gaps, &mut output, 0, DeltaMode::Checked).is_ok());
+        assert_eq!(output, [5, 15, u32::MAX - 5, u32::MAX]);
+
+        let gaps = crate::encode(&[5, 10, u32::MAX - 20, 6]);
+        assert!(matches!(
+            decode_delta_mode(&gaps, &mut output, 0, DeltaMode::Checked),
+            Err(StreamVbyteError::DeltaOverflow { index: 3 })
+        ));
+        assert!(matches!(
+            decode_delta_mode(&gaps, &mut output, u32::MAX, DeltaMode::Checked),
+            Err(StreamVbyteError::DeltaOverflow { index: 0 })
+        ));
+        assert_eq!(
+            decode_delta_mode(&gaps, &mut output, 0, DeltaMode::Wrapping).unwrap(),
+            gaps.len()
+        );
+        assert!(matches!(
+            decode_delta_mode(&gaps[..4], &mut output, 0, DeltaMode::Checked),
+            Err(StreamVbyteError::TruncatedStream(..))
+        ));
+    }
+}
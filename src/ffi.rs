@@ -0,0 +1,14 @@
+//! Bindings to functions of the C library which the -sys crate does not expose.
+
+extern "C" {
+    pub(crate) fn streamvbyte_encode_0124(
+        in_: *const u32,
+        length: u32,
+        out: *mut u8,
+    ) -> streamvbyte_sys::size_t;
+    pub(crate) fn streamvbyte_decode_0124(
+        in_: *const u8,
+        out: *mut u32,
+        length: u32,
+    ) -> streamvbyte_sys::size_t;
+}
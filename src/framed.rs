@@ -0,0 +1,822 @@
+//! Framed blocks which carry their codec and element count in a small header.
+//!
+//! A frame is laid out as `[magic][codec][count: u32 little endian][payload]`, where the payload
+//! is the regular vbyte encoding of `count` integers. Delta encoded frames always use an initial
+//! value of `0`.
+//!
+//! Checked frames, available with the `checksum` feature, are laid out as
+//! `[magic][codec][count: u32][crc32: u32][payload]`, where the little endian CRC-32 covers the payload.
+//!
+//! Self describing blocks are laid out as `[magic][codec][initial][count: varint][payload]`, where
+//! the little endian u32 `initial` is only present for delta encoded blocks. Sequences consisting
+//! of a single repeated value are stored as `[magic][constant tag][value][count: varint]`.
+
+use std::io::{self, Read, Write};
+
+#[cfg(feature = "checksum")]
+use crate::checksum::crc32;
+use crate::layout::{code_len, control_bytes_for, data_len_with};
+use crate::reference::value_code;
+use crate::{runs, Codec, StreamVbyteError};
+
+/// The first byte of every frame
+pub(crate) const FRAME_MAGIC: u8 = 0x56;
+
+/// The number of bytes preceding the payload of a frame
+pub(crate) const FRAME_HEADER_LEN: usize = 6;
+
+/// The first byte of every checked frame
+#[cfg(feature = "checksum")]
+const CHECKED_FRAME_MAGIC: u8 = 0x43;
+
+/// The number of bytes preceding the payload of a checked frame
+#[cfg(feature = "checksum")]
+const CHECKED_FRAME_HEADER_LEN: usize = 10;
+
+/// The first byte of every self describing block
+pub(crate) const SELF_DESCRIBING_MAGIC: u8 = 0x53;
+
+/// The maximum number of integers [`decode_self_describing`] allocates for a block, 64 MiB of output
+pub const SELF_DESCRIBING_MAX_COUNT: usize = 1 << 24;
+
+/// The codec byte of self describing blocks holding a single repeated value
+const CONSTANT_TAG: u8 = 0x80;
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Fills `buf` from `src`, returning `false` on a clean EOF before the first byte
+fn read_header<R: Read>(mut src: R, buf: &mut [u8]) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match src.read(&mut buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(true)
+}
+
+/// Appends exactly `len` bytes from `src` to `buf`, failing with `UnexpectedEof` on a short read
+fn read_to_vec<R: Read>(src: &mut R, len: usize, buf: &mut Vec<u8>) -> io::Result<()> {
+    let start = buf.len();
+    src.by_ref().take(len as u64).read_to_end(buf)?;
+    if buf.len() - start < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
+}
+
+/// Encode `input` with `codec` and write it as a single frame to `dst`.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{write_frame,Codec};
+/// let mut file = Vec::new();
+/// let bytes_written = write_frame(&mut file,&[1,2,44,5123,43,534],Codec::Plain).unwrap();
+/// assert_eq!(bytes_written,file.len());
+/// ```
+/// # Return
+///
+/// Returns the number of bytes written to `dst`
+///
+pub fn write_frame<W: Write>(mut dst: W, input: &[u32], codec: Codec) -> io::Result<usize> {
+    if input.len() > u32::MAX as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "frames can hold at most u32::MAX integers",
+        ));
+    }
+    let payload = codec.encode(input, 0);
+    let mut header = [0; FRAME_HEADER_LEN];
+    header[0] = FRAME_MAGIC;
+    header[1] = codec.tag();
+    header[2..].copy_from_slice(&(input.len() as u32).to_le_bytes());
+    dst.write_all(&header)?;
+    dst.write_all(&payload)?;
+    Ok(header.len() + payload.len())
+}
+
+/// Read and decode the next frame from `src`.
+///
+/// Only the bytes belonging to the frame are consumed, so consecutive frames can be read from the same reader.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{read_frame,write_frame,Codec};
+/// let mut file = Vec::new();
+/// write_frame(&mut file,&[1,2,44,5123,43,534],Codec::Delta).unwrap();
+/// let mut reader = &file[..];
+/// let (codec,values) = read_frame(&mut reader).unwrap().unwrap();
+/// assert_eq!(codec,Codec::Delta);
+/// assert_eq!(values,vec![1,2,44,5123,43,534]);
+/// assert!(read_frame(&mut reader).unwrap().is_none());
+/// ```
+/// # Return
+///
+/// Returns the codec and decoded integers of the frame or `None` if `src` is at EOF
+///
+pub fn read_frame<R: Read>(mut src: R) -> io::Result<Option<(Codec, Vec<u32>)>> {
+    let mut header = [0; FRAME_HEADER_LEN];
+    if !read_header(&mut src, &mut header)? {
+        return Ok(None);
+    }
+    if header[0] != FRAME_MAGIC {
+        return Err(invalid_data("invalid frame magic"));
+    }
+    let codec = Codec::from_tag(header[1]).ok_or_else(|| invalid_data("unknown frame codec"))?;
+    let count = u32::from_le_bytes([header[2], header[3], header[4], header[5]]) as usize;
+
+    // the count is untrusted, so the payload grows with the bytes actually read instead of being preallocated
+    let mut payload = Vec::new();
+    read_to_vec(&mut src, control_bytes_for(count), &mut payload)?;
+    let data_bytes = data_len_with(&payload, count, codec.code_lens());
+    read_to_vec(&mut src, data_bytes, &mut payload)?;
+
+    let mut values = vec![0; count];
+    codec
+        .decode_checked(&payload, &mut values, 0)
+        .map_err(|_| invalid_data("truncated frame payload"))?;
+    Ok(Some((codec, values)))
+}
+
+/// Parse the header of a frame held in memory into its codec and element count
+fn parse_frame_header(input: &[u8]) -> Result<(Codec, usize), StreamVbyteError> {
+    if input.len() < FRAME_HEADER_LEN {
+        return Err(StreamVbyteError::TruncatedStream(
+            input.len(),
+            FRAME_HEADER_LEN,
+        ));
+    }
+    if input[0] != FRAME_MAGIC {
+        return Err(StreamVbyteError::InvalidMagic(input[0], FRAME_MAGIC));
+    }
+    let codec = Codec::from_tag(input[1]).ok_or(StreamVbyteError::UnknownCodec(input[1]))?;
+    let count = u32::from_le_bytes([input[2], input[3], input[4], input[5]]) as usize;
+    Ok((codec, count))
+}
+
+/// Split a frame held in memory into its codec, element count and validated payload
+pub(crate) fn parse_frame(input: &[u8]) -> Result<(Codec, usize, &[u8]), StreamVbyteError> {
+    let (codec, count) = parse_frame_header(input)?;
+    let payload = &input[FRAME_HEADER_LEN..];
+    let payload_len = codec.encoded_size(payload, count)?;
+    Ok((codec, count, &payload[..payload_len]))
+}
+
+/// Read the number of integers stored in a frame produced by [`encode_framed`] or [`write_frame`] from its header.
+///
+/// The payload is not inspected, use [`framed_payload`] to also validate it.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode_framed,framed_count};
+/// let out_bytes = encode_framed(&[1,2,44,5123,43,534]);
+/// assert_eq!(framed_count(&out_bytes).unwrap(),6);
+/// assert!(framed_count(&out_bytes[..3]).is_err());
+/// ```
+/// # Return
+///
+/// Returns the number of integers, [`StreamVbyteError::TruncatedStream`] if `input` is shorter than the header,
+/// [`StreamVbyteError::InvalidMagic`] if it is not a frame or [`StreamVbyteError::UnknownCodec`]
+///
+pub fn framed_count(input: &[u8]) -> Result<usize, StreamVbyteError> {
+    parse_frame_header(input).map(|(_, count)| count)
+}
+
+/// Return the payload of a frame produced by [`encode_framed`] or [`write_frame`] without decoding it.
+///
+/// The payload is validated to hold all integers of the frame and any trailing bytes are excluded.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode,encode_framed,framed_payload};
+/// let out_bytes = encode_framed(&[1,2,44,5123,43,534]);
+/// assert_eq!(framed_payload(&out_bytes).unwrap(),&encode(&[1,2,44,5123,43,534])[..]);
+/// ```
+/// # Return
+///
+/// Returns the payload, or the errors of [`framed_count`] and [`StreamVbyteError::TruncatedStream`] if
+/// the payload is truncated
+///
+pub fn framed_payload(input: &[u8]) -> Result<&[u8], StreamVbyteError> {
+    parse_frame(input).map(|(_, _, payload)| payload)
+}
+
+/// Encode a sequence of u32 integers into a single frame held in memory, storing the number of
+/// integers alongside the payload.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_framed,encode_framed};
+/// let out_bytes = encode_framed(&[1,2,44,5123,43,534]);
+/// assert_eq!(decode_framed(&out_bytes).unwrap(),vec![1,2,44,5123,43,534]);
+/// ```
+/// # Panics
+///
+/// Panics if `input` holds more than `u32::MAX` integers
+///
+/// # Return
+///
+/// Returns the encoded frame as a byte buffer
+///
+pub fn encode_framed(input: &[u32]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(FRAME_HEADER_LEN + crate::max_compressedbytes(input.len()));
+    write_frame(&mut out, input, Codec::Plain).expect("frames can hold at most u32::MAX integers");
+    out
+}
+
+/// Decode a frame produced by [`encode_framed`] or [`write_frame`].
+///
+/// The header and payload length are validated before decoding, so a header claiming more integers
+/// than the payload holds returns an error. The input is never read past its end.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_framed,encode_framed};
+/// let out_bytes = encode_framed(&[1,2,44,5123,43,534]);
+/// assert!(decode_framed(&out_bytes[..out_bytes.len()-1]).is_err());
+/// ```
+/// # Return
+///
+/// Returns the decoded integers
+///
+pub fn decode_framed(input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
+    // the payload is validated before allocating, its unsliced form lets the decoder skip the padded copy
+    let (codec, count, _) = parse_frame(input)?;
+    let mut output = vec![0; count];
+    codec.decode_checked(&input[FRAME_HEADER_LEN..], &mut output, 0)?;
+    Ok(output)
+}
+
+/// Encode a sequence of u32 integers into a single frame like [`encode_framed`] which additionally stores
+/// a CRC-32 checksum of the payload.
+///
+/// Checked frames require the `checksum` feature. The checksum is computed in-crate, the feature only keeps
+/// the checked frame format out of builds which do not use it.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_framed_checked,encode_framed_checked,StreamVbyteError};
+/// let mut out_bytes = encode_framed_checked(&[1,2,44,5123,43,534]);
+/// assert_eq!(decode_framed_checked(&out_bytes).unwrap(),vec![1,2,44,5123,43,534]);
+/// out_bytes[12] ^= 0x10;
+/// assert!(matches!(decode_framed_checked(&out_bytes),Err(StreamVbyteError::ChecksumMismatch(..))));
+/// ```
+/// # Panics
+///
+/// Panics if `input` holds more than `u32::MAX` integers
+///
+/// # Return
+///
+/// Returns the encoded frame as a byte buffer
+///
+#[cfg(feature = "checksum")]
+pub fn encode_framed_checked(input: &[u32]) -> Vec<u8> {
+    assert!(
+        input.len() <= u32::MAX as usize,
+        "frames can hold at most u32::MAX integers"
+    );
+    let payload = Codec::Plain.encode(input, 0);
+    let mut out = Vec::with_capacity(CHECKED_FRAME_HEADER_LEN + payload.len());
+    out.push(CHECKED_FRAME_MAGIC);
+    out.push(Codec::Plain.tag());
+    out.extend_from_slice(&(input.len() as u32).to_le_bytes());
+    out.extend_from_slice(&crc32(&payload).to_le_bytes());
+    out.extend_from_slice(&payload);
+    out
+}
+
+/// Decode a frame produced by [`encode_framed_checked`] after verifying the checksum of its payload.
+///
+/// # Return
+///
+/// Returns the decoded integers, [`StreamVbyteError::ChecksumMismatch`] if the payload is corrupted or
+/// [`StreamVbyteError::TruncatedStream`] if the frame is truncated
+///
+#[cfg(feature = "checksum")]
+pub fn decode_framed_checked(input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
+    if input.len() < CHECKED_FRAME_HEADER_LEN {
+        return Err(StreamVbyteError::TruncatedStream(
+            input.len(),
+            CHECKED_FRAME_HEADER_LEN,
+        ));
+    }
+    if input[0] != CHECKED_FRAME_MAGIC {
+        return Err(StreamVbyteError::InvalidMagic(
+            input[0],
+            CHECKED_FRAME_MAGIC,
+        ));
+    }
+    let codec = Codec::from_tag(input[1]).ok_or(StreamVbyteError::UnknownCodec(input[1]))?;
+    let count = u32::from_le_bytes([input[2], input[3], input[4], input[5]]) as usize;
+    let checksum = u32::from_le_bytes([input[6], input[7], input[8], input[9]]);
+    let payload = &input[CHECKED_FRAME_HEADER_LEN..];
+    let actual = crc32(&payload[..codec.encoded_size(payload, count)?]);
+    if actual != checksum {
+        return Err(StreamVbyteError::ChecksumMismatch(actual, checksum));
+    }
+    let mut output = vec![0; count];
+    codec.decode_checked(payload, &mut output, 0)?;
+    Ok(output)
+}
+
+/// Migrate a stream of frames from `src` to `dst`, re-encoding every frame with the codec `to`.
+///
+/// Frames are processed one at a time, so only a single frame is held in memory.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{read_frame,recompress_stream,write_frame,Codec};
+/// let mut old = Vec::new();
+/// write_frame(&mut old,&[1,2,44,64,71,534],Codec::Plain).unwrap();
+/// write_frame(&mut old,&[5,6,7],Codec::Plain).unwrap();
+/// let mut new = Vec::new();
+/// let migrated = recompress_stream(&old[..],&mut new,Codec::Delta).unwrap();
+/// assert_eq!(migrated,9);
+/// let (codec,values) = read_frame(&new[..]).unwrap().unwrap();
+/// assert_eq!(codec,Codec::Delta);
+/// assert_eq!(values,vec![1,2,44,64,71,534]);
+/// ```
+/// # Return
+///
+/// Returns the total number of integers migrated
+///
+pub fn recompress_stream<R: Read, W: Write>(mut src: R, mut dst: W, to: Codec) -> io::Result<u64> {
+    let mut migrated = 0;
+    while let Some((_, values)) = read_frame(&mut src)? {
+        write_frame(&mut dst, &values, to)?;
+        migrated += values.len() as u64;
+    }
+    dst.flush()?;
+    Ok(migrated)
+}
+
+/// Append the LEB128 varint encoding of `value` to `out`
+fn write_varint(mut value: u64, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Read a LEB128 varint from the start of `input` returning the value and the bytes consumed
+fn read_varint(input: &[u8]) -> Result<(u64, usize), StreamVbyteError> {
+    let mut value = 0u64;
+    for (i, &byte) in input.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7F) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+    Err(StreamVbyteError::TruncatedStream(
+        input.len(),
+        input.len() + 1,
+    ))
+}
+
+/// Encode a sequence of u32 integers into a block which stores everything required to decode it.
+///
+/// # Arguments
+///
+/// * `input` - The input sequence of u32 integers
+/// * `codec` - The codec used to encode the payload
+/// * `initial` - The initial value used by delta codecs, see [`encode_delta`](crate::encode_delta). Ignored by other codecs.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_self_describing,encode_self_describing,Codec};
+/// let out_bytes = encode_self_describing(&[1,2,44,64,71,534],Codec::Delta,1);
+/// let recovered = decode_self_describing(&out_bytes).unwrap();
+/// assert_eq!(recovered,vec![1,2,44,64,71,534]);
+/// ```
+/// # Return
+///
+/// Returns the encoded block as a byte buffer
+///
+pub fn encode_self_describing(input: &[u32], codec: Codec, initial: u32) -> Vec<u8> {
+    let payload = codec.encode(input, initial);
+    let mut out = Vec::with_capacity(payload.len() + 16);
+    out.push(SELF_DESCRIBING_MAGIC);
+    out.push(codec.tag());
+    if codec == Codec::Delta {
+        out.extend_from_slice(&initial.to_le_bytes());
+    }
+    write_varint(input.len() as u64, &mut out);
+    out.extend_from_slice(&payload);
+    out
+}
+
+/// Decode a block produced by [`encode_self_describing`] or [`encode_best`] holding at most
+/// [`SELF_DESCRIBING_MAX_COUNT`] integers, see [`decode_self_describing_with_limit`].
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_self_describing,encode_self_describing,Codec};
+/// let out_bytes = encode_self_describing(&[1,2,44,5123,43,534],Codec::Plain,0);
+/// let recovered = decode_self_describing(&out_bytes).unwrap();
+/// assert_eq!(recovered,vec![1,2,44,5123,43,534]);
+/// assert!(decode_self_describing(&out_bytes[..out_bytes.len()-1]).is_err());
+/// ```
+/// # Return
+///
+/// Returns the decoded integers or [`StreamVbyteError::LengthTooLarge`] if the block holds more than
+/// [`SELF_DESCRIBING_MAX_COUNT`] integers
+///
+pub fn decode_self_describing(input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
+    decode_self_describing_with_limit(input, SELF_DESCRIBING_MAX_COUNT)
+}
+
+/// Decode a block produced by [`encode_self_describing`] or [`encode_best`] holding at most `max_count` integers.
+///
+/// The header and payload lengths are validated before decoding, so truncated or corrupted input returns an error.
+/// A block of a single repeated value stores only its count, so `max_count` bounds the number of integers
+/// allocated for untrusted input.
+///
+/// # Arguments
+///
+/// * `input` - The self describing block
+/// * `max_count` - The maximum number of integers the block may hold
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_self_describing_with_limit,encode_self_describing,Codec};
+/// let out_bytes = encode_self_describing(&[1,2,44,5123,43,534],Codec::Plain,0);
+/// let recovered = decode_self_describing_with_limit(&out_bytes,1000).unwrap();
+/// assert_eq!(recovered,vec![1,2,44,5123,43,534]);
+/// assert!(decode_self_describing_with_limit(&out_bytes[..out_bytes.len()-1],1000).is_err());
+/// assert!(decode_self_describing_with_limit(&out_bytes,5).is_err());
+/// ```
+/// # Return
+///
+/// Returns the decoded integers or [`StreamVbyteError::LengthTooLarge`] if the block holds more than
+/// `max_count` integers
+///
+pub fn decode_self_describing_with_limit(
+    input: &[u8],
+    max_count: usize,
+) -> Result<Vec<u32>, StreamVbyteError> {
+    if input.len() < 2 {
+        return Err(StreamVbyteError::TruncatedStream(input.len(), 2));
+    }
+    if input[0] != SELF_DESCRIBING_MAGIC {
+        return Err(StreamVbyteError::InvalidMagic(
+            input[0],
+            SELF_DESCRIBING_MAGIC,
+        ));
+    }
+    let read_u32 = |offset: usize| {
+        input
+            .get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or(StreamVbyteError::TruncatedStream(input.len(), offset + 4))
+    };
+    if input[1] == CONSTANT_TAG {
+        let value = read_u32(2)?;
+        let (count, _) = read_varint(&input[6..])?;
+        let count = checked_count(count, max_count)?;
+        return Ok(vec![value; count]);
+    }
+    let codec = Codec::from_tag(input[1]).ok_or(StreamVbyteError::UnknownCodec(input[1]))?;
+    let mut offset = 2;
+    let mut initial = 0;
+    if codec == Codec::Delta {
+        initial = read_u32(offset)?;
+        offset += 4;
+    }
+    let (count, varint_len) = read_varint(&input[offset..])?;
+    offset += varint_len;
+    let payload = &input[offset..];
+    // every four integers occupy at least one control byte so a larger count is truncated
+    let control_bytes = count.div_ceil(4);
+    if control_bytes > payload.len() as u64 {
+        return Err(StreamVbyteError::TruncatedStream(
+            payload.len(),
+            control_bytes.min(usize::MAX as u64) as usize,
+        ));
+    }
+    let count = checked_count(count, max_count)?;
+    codec.encoded_size(payload, count)?;
+    let mut output = vec![0; count];
+    codec.decode_checked(payload, &mut output, initial)?;
+    Ok(output)
+}
+
+/// Convert the `count` read from a block header, failing if it exceeds `max_count` or `u32::MAX`
+fn checked_count(count: u64, max_count: usize) -> Result<usize, StreamVbyteError> {
+    let limit = max_count.min(u32::MAX as usize) as u64;
+    if count > limit {
+        return Err(StreamVbyteError::LengthTooLarge(
+            count.min(usize::MAX as u64) as usize,
+        ));
+    }
+    Ok(count as usize)
+}
+
+/// Encode a sequence of u32 integers into a self describing block, storing sequences consisting of a single
+/// repeated value in a handful of bytes.
+///
+/// Blocks are decoded with [`decode_self_describing`].
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_self_describing,encode_best};
+/// let input = vec![u32::MAX;10000];
+/// let out_bytes = encode_best(&input);
+/// assert!(out_bytes.len() < 10);
+/// assert_eq!(decode_self_describing(&out_bytes).unwrap(),input);
+/// ```
+/// # Return
+///
+/// Returns the encoded block as a byte buffer
+///
+pub fn encode_best(input: &[u32]) -> Vec<u8> {
+    let mut input_runs = runs(input);
+    match (input_runs.next(), input_runs.next()) {
+        // the run stores the value in 4 bytes instead of the control and data sections of the plain block
+        (Some((value, _)), None)
+            if control_bytes_for(input.len()) + input.len() * code_len(value_code(value)) > 4 =>
+        {
+            let mut out = vec![SELF_DESCRIBING_MAGIC, CONSTANT_TAG];
+            out.extend_from_slice(&value.to_le_bytes());
+            write_varint(input.len() as u64, &mut out);
+            out
+        }
+        _ => encode_self_describing(input, Codec::Plain, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recompress_roundtrip() {
+        let blocks: Vec<Vec<u32>> = (0..10)
+            .map(|b| (0..(b * 1001)).map(|v| v * 3 + b).collect())
+            .collect();
+        let mut src = Vec::new();
+        for block in &blocks {
+            write_frame(&mut src, block, Codec::Plain).unwrap();
+        }
+        let mut dst = Vec::new();
+        let migrated = recompress_stream(&src[..], &mut dst, Codec::Delta).unwrap();
+        assert_eq!(migrated, blocks.iter().map(|b| b.len() as u64).sum::<u64>());
+        assert!(dst.len() < src.len());
+
+        let mut reader = &dst[..];
+        for block in &blocks {
+            let (codec, values) = read_frame(&mut reader).unwrap().unwrap();
+            assert_eq!(codec, Codec::Delta);
+            assert_eq!(&values, block);
+        }
+        assert!(read_frame(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn read_frame_rejects_garbage() {
+        let err = read_frame(&[0u8, 0, 0, 0, 0, 0][..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut src = Vec::new();
+        write_frame(&mut src, &[1, 2, 3, 4, 5], Codec::Plain).unwrap();
+        let err = read_frame(&src[..src.len() - 1]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        // a corrupted count must not be trusted for the size of the payload
+        let mut header = vec![FRAME_MAGIC, Codec::Plain.tag()];
+        header.extend_from_slice(&u32::MAX.to_le_bytes());
+        header.extend_from_slice(&[0; 7]);
+        let err = read_frame(&header[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn read_frame_of_single_byte_values() {
+        // the payload is read into a buffer of exactly its size, which the decoder must not read past
+        let mut src = Vec::new();
+        for len in [1, 32, 33, 1001] {
+            write_frame(&mut src, &vec![1; len], Codec::Plain).unwrap();
+        }
+        let mut reader = &src[..];
+        for len in [1, 32, 33, 1001] {
+            assert_eq!(read_frame(&mut reader).unwrap().unwrap().1, vec![1; len]);
+        }
+    }
+
+    #[test]
+    fn framed_rejects_oversized_count() {
+        let input: Vec<u32> = (0..1000).map(|v| v * 31).collect();
+        let mut out_bytes = encode_framed(&input);
+        assert_eq!(decode_framed(&out_bytes).unwrap(), input);
+
+        out_bytes[2..6].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            decode_framed(&out_bytes),
+            Err(StreamVbyteError::TruncatedStream(..))
+        ));
+        assert!(matches!(
+            decode_framed(&out_bytes[..3]),
+            Err(StreamVbyteError::TruncatedStream(3, FRAME_HEADER_LEN))
+        ));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn framed_of_exactly_sized_input() {
+        use crate::guard::GuardedBytes;
+        for input in [vec![1; 32], crate::test_input(1001)] {
+            let guarded = GuardedBytes::new(&encode_framed(&input));
+            assert_eq!(decode_framed(guarded.as_slice()).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn framed_inspection_reads_header() {
+        let input: Vec<u32> = (0..1000).map(|v| v * 31).collect();
+        let mut out_bytes = encode_framed(&input);
+        assert_eq!(framed_count(&out_bytes).unwrap(), input.len());
+        let payload_len = framed_payload(&out_bytes).unwrap().len();
+        assert_eq!(payload_len, out_bytes.len() - FRAME_HEADER_LEN);
+
+        out_bytes.extend_from_slice(&[1, 2, 3]);
+        assert_eq!(framed_payload(&out_bytes).unwrap().len(), payload_len);
+        out_bytes.truncate(FRAME_HEADER_LEN + 10);
+        assert_eq!(framed_count(&out_bytes).unwrap(), input.len());
+        assert!(matches!(
+            framed_payload(&out_bytes),
+            Err(StreamVbyteError::TruncatedStream(..))
+        ));
+        out_bytes[1] = 0x7f;
+        assert!(matches!(
+            framed_count(&out_bytes),
+            Err(StreamVbyteError::UnknownCodec(0x7f))
+        ));
+        assert!(matches!(
+            framed_count(&encode_self_describing(&input, Codec::Plain, 0)),
+            Err(StreamVbyteError::InvalidMagic(
+                SELF_DESCRIBING_MAGIC,
+                FRAME_MAGIC
+            ))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "checksum")]
+    fn checked_frames_detect_corruption() {
+        let input: Vec<u32> = (0..1000).map(|v| v * 31).collect();
+        let out_bytes = encode_framed_checked(&input);
+        assert_eq!(decode_framed_checked(&out_bytes).unwrap(), input);
+        assert!(decode_framed_checked(&encode_framed_checked(&[]))
+            .unwrap()
+            .is_empty());
+
+        // corrupting the stored checksum or the data section keeps the payload length intact
+        for pos in [
+            CHECKED_FRAME_HEADER_LEN - 1,
+            out_bytes.len() / 2,
+            out_bytes.len() - 1,
+        ] {
+            let mut corrupted = out_bytes.clone();
+            corrupted[pos] ^= 0x01;
+            assert!(matches!(
+                decode_framed_checked(&corrupted),
+                Err(StreamVbyteError::ChecksumMismatch(..))
+            ));
+        }
+        assert!(matches!(
+            decode_framed_checked(&out_bytes[..out_bytes.len() - 1]),
+            Err(StreamVbyteError::TruncatedStream(..))
+        ));
+        assert!(matches!(
+            decode_framed_checked(&encode_framed(&input)),
+            Err(StreamVbyteError::InvalidMagic(
+                FRAME_MAGIC,
+                CHECKED_FRAME_MAGIC
+            ))
+        ));
+    }
+
+    #[test]
+    fn self_describing_roundtrip() {
+        for len in [0, 1, 3, 4, 127, 128, 1000, 20000] {
+            let input: Vec<u32> = (0..len).map(|v| v * 7 + (v >> 3)).collect();
+            for &codec in &[
+                Codec::Plain,
+                Codec::Delta,
+                Codec::Plain0124,
+                Codec::DeltaZigzag,
+            ] {
+                let out_bytes = encode_self_describing(&input, codec, 0);
+                assert_eq!(decode_self_describing(&out_bytes).unwrap(), input);
+                for cut in [1, 2, out_bytes.len() / 2] {
+                    if cut < out_bytes.len() {
+                        assert!(
+                            decode_self_describing(&out_bytes[..out_bytes.len() - cut]).is_err()
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn self_describing_rejects_invalid_header() {
+        let mut out_bytes = encode_self_describing(&[1, 2, 3], Codec::Plain, 0);
+        out_bytes[1] = 0xFF;
+        assert!(matches!(
+            decode_self_describing(&out_bytes),
+            Err(StreamVbyteError::UnknownCodec(0xFF))
+        ));
+        out_bytes[0] = 0;
+        assert!(matches!(
+            decode_self_describing(&out_bytes),
+            Err(StreamVbyteError::InvalidMagic(0, SELF_DESCRIBING_MAGIC))
+        ));
+    }
+
+    #[test]
+    fn encode_best_constant_runs() {
+        for &value in &[0, 1, 300, u32::MAX] {
+            for len in [1, 4, 5, 100000] {
+                let input = vec![value; len];
+                let out_bytes = encode_best(&input);
+                assert!(out_bytes.len() <= 2 + 4 + 3);
+                assert_eq!(decode_self_describing(&out_bytes).unwrap(), input);
+            }
+        }
+        let input = vec![5, 5, 5, 6];
+        assert_eq!(
+            encode_best(&input),
+            encode_self_describing(&input, Codec::Plain, 0)
+        );
+        assert_eq!(
+            decode_self_describing(&encode_best(&[])).unwrap(),
+            Vec::<u32>::new()
+        );
+        assert!(decode_self_describing(&encode_best(&[7; 3])[..5]).is_err());
+
+        // a run is only stored if it is smaller than the plain block
+        for input in [vec![7], vec![7; 3], vec![300; 2], vec![u32::MAX]] {
+            let plain = encode_self_describing(&input, Codec::Plain, 0);
+            let out_bytes = encode_best(&input);
+            assert!(out_bytes.len() <= plain.len());
+            assert_eq!(decode_self_describing(&out_bytes).unwrap(), input);
+        }
+        assert_eq!(encode_best(&[7]).len(), 5);
+        assert_eq!(
+            encode_best(&[7; 3]),
+            encode_self_describing(&[7; 3], Codec::Plain, 0)
+        );
+    }
+
+    #[test]
+    fn self_describing_limits_count() {
+        let input = vec![5; 1000];
+        let out_bytes = encode_best(&input);
+        assert_eq!(
+            decode_self_describing_with_limit(&out_bytes, 1000).unwrap(),
+            input
+        );
+        assert!(matches!(
+            decode_self_describing_with_limit(&out_bytes, 999),
+            Err(StreamVbyteError::LengthTooLarge(1000))
+        ));
+        let out_bytes = encode_self_describing(&input, Codec::Plain, 0);
+        assert!(matches!(
+            decode_self_describing_with_limit(&out_bytes, 999),
+            Err(StreamVbyteError::LengthTooLarge(1000))
+        ));
+
+        // a forged run of u32::MAX integers fits in a few bytes and must not be materialized
+        let mut forged = vec![SELF_DESCRIBING_MAGIC, CONSTANT_TAG, 1, 0, 0, 0];
+        write_varint(u64::from(u32::MAX), &mut forged);
+        assert!(matches!(
+            decode_self_describing_with_limit(&forged, 1 << 20),
+            Err(StreamVbyteError::LengthTooLarge(len)) if len == u32::MAX as usize
+        ));
+        let mut forged = vec![SELF_DESCRIBING_MAGIC, CONSTANT_TAG, 1, 0, 0, 0];
+        write_varint(SELF_DESCRIBING_MAX_COUNT as u64 + 1, &mut forged);
+        assert!(matches!(
+            decode_self_describing(&forged),
+            Err(StreamVbyteError::LengthTooLarge(len)) if len == SELF_DESCRIBING_MAX_COUNT + 1
+        ));
+    }
+}
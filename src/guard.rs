@@ -0,0 +1,75 @@
+//! Test buffers ending right before an inaccessible page, so any read past their end faults.
+
+use core::ptr;
+
+const PROT_NONE: i32 = 0;
+const PROT_READ: i32 = 1;
+const PROT_WRITE: i32 = 2;
+const MAP_PRIVATE: i32 = 0x02;
+const MAP_ANONYMOUS: i32 = 0x20;
+const SC_PAGESIZE: i32 = 30;
+
+extern "C" {
+    fn mmap(addr: *mut u8, len: usize, prot: i32, flags: i32, fd: i32, offset: i64) -> *mut u8;
+    fn mprotect(addr: *mut u8, len: usize, prot: i32) -> i32;
+    fn munmap(addr: *mut u8, len: usize) -> i32;
+    fn sysconf(name: i32) -> i64;
+}
+
+/// A copy of some bytes placed at the end of a mapping whose following page is `PROT_NONE`
+pub(crate) struct GuardedBytes {
+    map: *mut u8,
+    map_len: usize,
+    start: *const u8,
+    len: usize,
+}
+
+impl GuardedBytes {
+    /// Copy `bytes` so they end exactly at the start of the guard page
+    pub(crate) fn new(bytes: &[u8]) -> GuardedBytes {
+        // SAFETY: sysconf has no preconditions
+        let page = unsafe { sysconf(SC_PAGESIZE) } as usize;
+        let data_pages = bytes.len().div_ceil(page).max(1);
+        let map_len = (data_pages + 1) * page;
+        // SAFETY: an anonymous private mapping does not alias any Rust memory
+        let map = unsafe {
+            mmap(
+                ptr::null_mut(),
+                map_len,
+                PROT_READ | PROT_WRITE,
+                MAP_PRIVATE | MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        assert!(map as isize != -1, "mmap failed");
+        // SAFETY: the mapping is `map_len` bytes long and `bytes` fits in front of its last page
+        let start = unsafe {
+            let guard = map.add(data_pages * page);
+            assert_eq!(mprotect(guard, page, PROT_NONE), 0, "mprotect failed");
+            let start = guard.sub(bytes.len());
+            ptr::copy_nonoverlapping(bytes.as_ptr(), start, bytes.len());
+            start
+        };
+        GuardedBytes {
+            map,
+            map_len,
+            start,
+            len: bytes.len(),
+        }
+    }
+
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        // SAFETY: the `len` bytes before the guard page are initialized and readable
+        unsafe { core::slice::from_raw_parts(self.start, self.len) }
+    }
+}
+
+impl Drop for GuardedBytes {
+    fn drop(&mut self) {
+        // SAFETY: the mapping was created by `new` and is no longer borrowed
+        unsafe {
+            munmap(self.map, self.map_len);
+        }
+    }
+}
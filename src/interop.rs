@@ -0,0 +1,100 @@
+//! Byte for byte test vectors of the C library, pinning the layout shared with other streamvbyte users.
+//!
+//! The inputs span more than one block of sixteen integers, so the SIMD paths as well as the scalar
+//! tail of the C encoders and decoders are covered.
+
+/// An input of 19 integers of all widths and its encoding by `streamvbyte_encode`
+const PLAIN_INPUT: [u32; 19] = [
+    0, 82951117, 990140, 111948, 1933, 11, 2, 175147642, 15842253, 294810, 2954, 408, 6, 73963262,
+    43786910, 567300, 58231, 1037, 7,
+];
+const PLAIN_BYTES: [u8; 51] = [
+    0xAC, 0xC1, 0x5A, 0xBC, 0x05, 0x00, 0xCD, 0xBB, 0xF1, 0x04, 0xBC, 0x1B, 0x0F, 0x4C, 0xB5, 0x01,
+    0x8D, 0x07, 0x0B, 0x02, 0x7A, 0x8A, 0x70, 0x0A, 0xCD, 0xBB, 0xF1, 0x9A, 0x7F, 0x04, 0x8A, 0x0B,
+    0x98, 0x01, 0x06, 0xFE, 0x96, 0x68, 0x04, 0x9E, 0x22, 0x9C, 0x02, 0x04, 0xA8, 0x08, 0x77, 0xE3,
+    0x0D, 0x04, 0x07,
+];
+
+/// A non decreasing input of 19 integers and its encoding by `streamvbyte_delta_encode` with `DELTA_INITIAL`
+const DELTA_INPUT: [u32; 19] = [
+    1000, 1037, 1111, 1222, 1370, 141370, 141592, 141851, 142147, 142180, 212180, 212287, 212431,
+    212612, 212830, 212830, 213122, 213151, 213217,
+];
+const DELTA_INITIAL: u32 = 990;
+const DELTA_BYTES: [u8; 31] = [
+    0x00, 0x48, 0x21, 0x00, 0x01, 0x0A, 0x25, 0x4A, 0x6F, 0x94, 0xE0, 0x22, 0x02, 0xDE, 0x03, 0x01,
+    0x28, 0x01, 0x21, 0x70, 0x11, 0x01, 0x6B, 0x90, 0xB5, 0xDA, 0x00, 0x24, 0x01, 0x1D, 0x42,
+];
+
+/// An input of 18 integers dominated by zeros and its encoding by `streamvbyte_encode_0124`
+const ZERO124_INPUT: [u32; 18] = [
+    0,
+    0,
+    7,
+    0,
+    300,
+    0,
+    0,
+    70000,
+    0,
+    255,
+    256,
+    65535,
+    65536,
+    0,
+    u32::MAX,
+    1,
+    0,
+    0,
+];
+const ZERO124_BYTES: [u8; 26] = [
+    0x10, 0xC2, 0xA4, 0x73, 0x00, 0x07, 0x2C, 0x01, 0x70, 0x11, 0x01, 0x00, 0xFF, 0x00, 0x01, 0xFF,
+    0xFF, 0x00, 0x00, 0x01, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x01,
+];
+
+#[test]
+fn plain_matches_c_layout() {
+    assert_eq!(crate::encode(&PLAIN_INPUT), PLAIN_BYTES);
+    assert_eq!(
+        crate::reference::encode_reference(&PLAIN_INPUT),
+        PLAIN_BYTES
+    );
+    let mut recovered = [0; 19];
+    assert_eq!(
+        crate::decode(&PLAIN_BYTES, &mut recovered),
+        PLAIN_BYTES.len()
+    );
+    assert_eq!(recovered, PLAIN_INPUT);
+    // the control section holds ceil(19 / 4) bytes and is followed by the data section
+    let (control, data) = crate::section_sizes(&PLAIN_BYTES, 19);
+    assert_eq!((control, control + data), (5, PLAIN_BYTES.len()));
+}
+
+#[test]
+fn delta_matches_c_layout() {
+    assert_eq!(
+        crate::encode_delta(&DELTA_INPUT, DELTA_INITIAL),
+        DELTA_BYTES
+    );
+    assert_eq!(
+        crate::reference::encode_delta_reference(&DELTA_INPUT, DELTA_INITIAL),
+        DELTA_BYTES
+    );
+    let mut recovered = [0; 19];
+    assert_eq!(
+        crate::decode_delta(&DELTA_BYTES, &mut recovered, DELTA_INITIAL),
+        DELTA_BYTES.len()
+    );
+    assert_eq!(recovered, DELTA_INPUT);
+}
+
+#[test]
+fn zero124_matches_c_layout() {
+    assert_eq!(crate::encode_0124(&ZERO124_INPUT), ZERO124_BYTES);
+    let mut recovered = [1; 18];
+    assert_eq!(
+        crate::decode_0124(&ZERO124_BYTES, &mut recovered),
+        ZERO124_BYTES.len()
+    );
+    assert_eq!(recovered, ZERO124_INPUT);
+}
@@ -0,0 +1,436 @@
+//! Lazy decoding of vbyte encoded buffers without materializing the whole output, and encoding from iterators.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::layout::{code_len, control_bytes_for, data_len};
+use crate::reference::decode_value;
+#[cfg(feature = "alloc")]
+use crate::reference::encode_value;
+
+/// Iterator decoding a vbyte encoded buffer one block of four integers at a time, see [`decode_iter`]
+#[derive(Debug, Clone)]
+pub struct DecodeIter<'a> {
+    control: &'a [u8],
+    data: &'a [u8],
+    /// The number of integers not yet decoded into `block`
+    undecoded: usize,
+    block: [u32; 4],
+    block_pos: usize,
+    block_len: usize,
+    /// The running prefix sum of delta encoded buffers
+    prev: Option<u32>,
+}
+
+impl<'a> DecodeIter<'a> {
+    /// Panics if `input` is shorter than the control section of `count` integers
+    fn new(input: &'a [u8], count: usize, prev: Option<u32>) -> DecodeIter<'a> {
+        let (control, data) = input.split_at(control_bytes_for(count));
+        DecodeIter {
+            control,
+            data,
+            undecoded: count,
+            block: [0; 4],
+            block_pos: 0,
+            block_len: 0,
+            prev,
+        }
+    }
+
+    /// Decode the next block of up to four integers into `block`
+    fn fill_block(&mut self) {
+        let (&key, control) = self.control.split_first().expect("control byte per block");
+        self.control = control;
+        self.block_len = self.undecoded.min(4);
+        self.block_pos = 0;
+        self.undecoded -= self.block_len;
+        for i in 0..self.block_len {
+            let code = (key >> (2 * i)) & 0b11;
+            let mut value = decode_value(code, self.data);
+            self.data = &self.data[code_len(code)..];
+            if let Some(prev) = self.prev.as_mut() {
+                value = prev.wrapping_add(value);
+                *prev = value;
+            }
+            self.block[i] = value;
+        }
+    }
+}
+
+impl Iterator for DecodeIter<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.block_pos == self.block_len {
+            if self.undecoded == 0 {
+                return None;
+            }
+            self.fill_block();
+        }
+        let value = self.block[self.block_pos];
+        self.block_pos += 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.undecoded + self.block_len - self.block_pos;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for DecodeIter<'_> {}
+
+impl core::iter::FusedIterator for DecodeIter<'_> {}
+
+/// A cursor decoding a buffer of `count` integers encoded by [`encode`](crate::encode) one block of four at a time.
+///
+/// The cursor remembers the integer and data byte offset it stopped at, so decoding can be resumed
+/// across calls.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode,DecodeCursor};
+/// let out_buf = encode(&[1,2,44,5123,43,534]);
+/// let mut cursor = DecodeCursor::new(&out_buf,6);
+/// let mut block = [0;4];
+/// assert_eq!(cursor.next_block(&mut block),Some(4));
+/// assert_eq!(block,[1,2,44,5123]);
+/// assert_eq!(cursor.remaining(),2);
+/// assert_eq!(cursor.next_block(&mut block),Some(2));
+/// assert_eq!(&block[..2],&[43,534]);
+/// assert_eq!(cursor.next_block(&mut block),None);
+/// assert_eq!(cursor.byte_offset(),out_buf.len());
+/// ```
+#[derive(Debug, Clone)]
+pub struct DecodeCursor<'a> {
+    input: &'a [u8],
+    count: usize,
+    /// The number of integers decoded so far
+    position: usize,
+    /// The offset of the data bytes of the next integer in `input`
+    offset: usize,
+}
+
+impl<'a> DecodeCursor<'a> {
+    /// Create a cursor at the start of the encoding of `count` integers in `input`
+    pub fn new(input: &'a [u8], count: usize) -> DecodeCursor<'a> {
+        DecodeCursor {
+            input,
+            count,
+            position: 0,
+            offset: control_bytes_for(count),
+        }
+    }
+
+    /// Decode the next block of up to four integers into `out` and advance the cursor.
+    ///
+    /// Only the final block can hold fewer than four integers, the remaining elements of `out` are
+    /// left untouched in that case.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input` is too short to hold `count` integers
+    ///
+    /// # Return
+    ///
+    /// Returns the number of integers written to `out`, or `None` once all integers are decoded
+    pub fn next_block(&mut self, out: &mut [u32; 4]) -> Option<usize> {
+        if self.position == self.count {
+            return None;
+        }
+        let block_len = (self.count - self.position).min(4);
+        let key = self.input[self.position / 4];
+        for (i, value) in out.iter_mut().take(block_len).enumerate() {
+            let code = (key >> (2 * i)) & 0b11;
+            *value = decode_value(code, &self.input[self.offset..]);
+            self.offset += code_len(code);
+        }
+        self.position += block_len;
+        Some(block_len)
+    }
+
+    /// The number of integers not yet decoded
+    pub fn remaining(&self) -> usize {
+        self.count - self.position
+    }
+
+    /// The number of integers decoded so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The offset in `input` of the data bytes of the next integer, `input.len()` once a whole buffer is decoded
+    pub fn byte_offset(&self) -> usize {
+        self.offset
+    }
+}
+
+/// Lazily decode `count` u32 integers encoded by [`encode`](crate::encode).
+///
+/// Only a single block of four integers is held in memory at any time.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_iter,encode};
+/// let out_buf = encode(&[1,2,44,5123,43,534]);
+/// let mut values = decode_iter(&out_buf,6);
+/// assert_eq!(values.len(),6);
+/// assert_eq!(values.next(),Some(1));
+/// assert_eq!(values.collect::<Vec<u32>>(),vec![2,44,5123,43,534]);
+/// ```
+/// # Panics
+///
+/// Panics on construction if `input` is shorter than the control section of `count` integers and while
+/// iterating if it is too short to hold their data
+///
+/// # Return
+///
+/// Returns an iterator yielding the decoded integers
+///
+pub fn decode_iter(input: &[u8], count: usize) -> DecodeIter<'_> {
+    DecodeIter::new(input, count, None)
+}
+
+/// Lazily decode `count` non decreasing u32 integers encoded by [`encode_delta`](crate::encode_delta).
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_delta_iter,encode_delta};
+/// let out_buf = encode_delta(&[1,2,44,64,71,534],1);
+/// let values: Vec<u32> = decode_delta_iter(&out_buf,6,1).collect();
+/// assert_eq!(values,vec![1,2,44,64,71,534]);
+/// ```
+/// # Panics
+///
+/// Panics on construction if `input` is shorter than the control section of `count` integers and while
+/// iterating if it is too short to hold their data
+///
+/// # Return
+///
+/// Returns an iterator yielding the decoded integers
+///
+pub fn decode_delta_iter(input: &[u8], count: usize, initial: u32) -> DecodeIter<'_> {
+    DecodeIter::new(input, count, Some(initial))
+}
+
+/// Decode only the first `n` integers of a buffer encoded by [`encode`](crate::encode) into `output[..n]`.
+///
+/// The data section of the regular encoding starts after the control bytes of all integers, so `output`
+/// **MUST** be the same size as the original input sequence like for [`decode`](crate::decode). Only the
+/// control bytes and data bytes of the first `n` integers are read and `output[n..]` is left untouched.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_prefix,encode};
+/// let out_buf = encode(&[1,2,44,5123,43,534]);
+/// let mut recovered = vec![0;6];
+/// let bytes_read = decode_prefix(&out_buf,&mut recovered,5);
+/// assert_eq!(bytes_read,out_buf.len()-2);
+/// assert_eq!(recovered,vec![1,2,44,5123,43,0]);
+/// ```
+/// # Panics
+///
+/// Panics if `n` exceeds `output.len()` or `input` is too short to hold the first `n` integers
+///
+/// # Return
+///
+/// Returns the number of bytes of `input` up to the end of the data of the `n`-th integer
+///
+pub fn decode_prefix(input: &[u8], output: &mut [u32], n: usize) -> usize {
+    assert!(
+        n <= output.len(),
+        "n {} exceeds the output len {}",
+        n,
+        output.len()
+    );
+    let (control, data) = input.split_at(control_bytes_for(output.len()));
+    let mut offset = 0;
+    for (i, out) in output[..n].iter_mut().enumerate() {
+        let code = (control[i / 4] >> (2 * (i % 4))) & 0b11;
+        *out = decode_value(code, &data[offset..]);
+        offset += code_len(code);
+    }
+    control.len() + offset
+}
+
+/// Decode only the integer at position `index` of `count` integers encoded by [`encode`](crate::encode).
+///
+/// The data offset of the value is found by summing the lengths of the preceding control codes, so only
+/// the first `index / 4 + 1` control bytes and the bytes of the value itself are read. Delta encoded buffers
+/// store gaps and require decoding all preceding values, use [`encode_delta_blocked`](crate::encode_delta_blocked)
+/// for random access into delta encoded sequences.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode,get};
+/// let out_buf = encode(&[1,2,44,5123,43,534]);
+/// assert_eq!(get(&out_buf,6,3),5123);
+/// assert_eq!(get(&out_buf,6,5),534);
+/// ```
+/// # Panics
+///
+/// Panics if `index` is not smaller than `count` or `input` is too short to hold `count` integers
+///
+/// # Return
+///
+/// Returns the integer at position `index`
+///
+pub fn get(input: &[u8], count: usize, index: usize) -> u32 {
+    assert!(
+        index < count,
+        "index {} out of bounds for length {}",
+        index,
+        count
+    );
+    let control_bytes = control_bytes_for(count);
+    let offset = control_bytes + data_len(&input[..control_bytes], index);
+    let code = (input[index / 4] >> (2 * (index % 4))) & 0b11;
+    decode_value(code, &input[offset..])
+}
+
+/// Encode the u32 integers yielded by `iter` into the same representation as [`encode`](crate::encode).
+///
+/// The integers are not collected, the control and data sections are built up separately and joined
+/// at the end, so only the output is held in memory.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode,encode,encode_from_iter};
+/// let (out_bytes,count) = encode_from_iter((1..=6).map(|v| v * 1000));
+/// assert_eq!(count,6);
+/// assert_eq!(out_bytes,encode(&[1000,2000,3000,4000,5000,6000]));
+/// let mut recovered = vec![0;count];
+/// decode(&out_bytes,&mut recovered);
+/// assert_eq!(recovered,vec![1000,2000,3000,4000,5000,6000]);
+/// ```
+/// # Return
+///
+/// Returns the encoded output as a byte buffer and the number of encoded integers, which is required to decode it
+///
+#[cfg(feature = "alloc")]
+pub fn encode_from_iter<I: IntoIterator<Item = u32>>(iter: I) -> (Vec<u8>, usize) {
+    let iter = iter.into_iter();
+    let (lower, _) = iter.size_hint();
+    let mut control = Vec::with_capacity(control_bytes_for(lower));
+    let mut data = Vec::with_capacity(lower);
+    let mut count = 0;
+    for value in iter {
+        if count % 4 == 0 {
+            control.push(0);
+        }
+        let code = encode_value(value, &mut data);
+        // unwrap ok as a control byte was pushed for every block of four integers
+        *control.last_mut().unwrap() |= code << (2 * (count % 4));
+        count += 1;
+    }
+    control.extend_from_slice(&data);
+    (control, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_iter_matches_decode() {
+        for len in [0u32, 1, 2, 3, 4, 5, 7, 8, 1001] {
+            let input = crate::test_input(len);
+            let out_bytes = crate::encode(&input);
+            let mut values = decode_iter(&out_bytes, input.len());
+            for (i, &expected) in input.iter().enumerate() {
+                assert_eq!(values.size_hint(), (input.len() - i, Some(input.len() - i)));
+                assert_eq!(values.next(), Some(expected));
+            }
+            assert_eq!(values.next(), None);
+            assert_eq!(values.len(), 0);
+
+            let ascending: Vec<u32> = input
+                .iter()
+                .scan(5, |sum, &v| {
+                    *sum += v >> 12;
+                    Some(*sum)
+                })
+                .collect();
+            let out_bytes = crate::encode_delta(&ascending, 5);
+            let values: Vec<u32> = decode_delta_iter(&out_bytes, ascending.len(), 5).collect();
+            assert_eq!(values, ascending);
+        }
+    }
+
+    #[test]
+    fn decode_prefix_reads_only_prefix() {
+        let input = crate::test_input(1001u32);
+        let out_bytes = crate::encode(&input);
+        for n in [0, 1, 3, 4, 5, 500, 1000, 1001] {
+            let mut recovered = vec![u32::MAX; input.len()];
+            let bytes_read = decode_prefix(&out_bytes, &mut recovered, n);
+            assert_eq!(&recovered[..n], &input[..n]);
+            assert!(recovered[n..].iter().all(|&v| v == u32::MAX));
+
+            let (control_bytes, _) = crate::section_sizes(&out_bytes, input.len());
+            let prefix_data: usize = input[..n]
+                .iter()
+                .map(|&v| code_len(crate::reference::value_code(v)))
+                .sum();
+            assert_eq!(bytes_read, control_bytes + prefix_data);
+            // the data beyond the prefix is never read
+            decode_prefix(&out_bytes[..bytes_read], &mut recovered, n);
+        }
+        assert_eq!(
+            decode_prefix(&out_bytes, &mut vec![0; input.len()], 1001),
+            out_bytes.len()
+        );
+    }
+
+    #[test]
+    fn decode_cursor_matches_decode() {
+        for len in [0u32, 1, 3, 4, 5, 1001] {
+            let input = crate::test_input(len);
+            let out_bytes = crate::encode(&input);
+            let mut cursor = DecodeCursor::new(&out_bytes, input.len());
+            let mut block = [0; 4];
+            let mut recovered = Vec::new();
+            while let Some(n) = cursor.next_block(&mut block) {
+                assert!(n == 4 || cursor.remaining() == 0);
+                recovered.extend_from_slice(&block[..n]);
+                assert_eq!(cursor.position(), recovered.len());
+            }
+            assert_eq!(recovered, input);
+            assert_eq!(cursor.remaining(), 0);
+            assert_eq!(cursor.byte_offset(), out_bytes.len());
+        }
+    }
+
+    #[test]
+    fn get_matches_decode() {
+        let input = crate::test_input(1001u32);
+        let out_bytes = crate::encode(&input);
+        for (i, &expected) in input.iter().enumerate() {
+            assert_eq!(get(&out_bytes, input.len(), i), expected);
+        }
+    }
+
+    #[test]
+    fn encode_from_iter_matches_encode() {
+        for len in [0u32, 1, 3, 4, 5, 1001] {
+            let input = crate::test_input(len);
+            // filter hides the exact length from the size hint
+            let (out_bytes, count) = encode_from_iter(input.iter().copied().filter(|_| true));
+            assert_eq!(count, input.len());
+            assert_eq!(out_bytes, crate::encode(&input));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn decode_iter_panics_on_short_control_section() {
+        let out_bytes = crate::encode(&[1, 2, 3, 4, 5]);
+        decode_iter(&out_bytes[..1], 5);
+    }
+}
@@ -0,0 +1,138 @@
+//! Encoding of sequences holding more than `u32::MAX` integers.
+//!
+//! The C library takes the number of integers as a u32, so larger inputs are split into chunks. The
+//! output is laid out as `[count: u64][chunk_len: u32]` followed by a `[len: u64][payload]` entry per
+//! chunk, where all integers are little endian and every chunk but the last holds `chunk_len` integers.
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use crate::{encode_to_buf, max_compressedbytes, Codec, StreamVbyteError};
+
+/// The number of integers per chunk, the largest multiple of four not exceeding `u32::MAX`
+const LARGE_CHUNK_LEN: usize = u32::MAX as usize & !3;
+
+/// The number of bytes preceding the first chunk
+const LARGE_HEADER_LEN: usize = 12;
+
+fn encode_chunked(input: &[u32], chunk_len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(LARGE_HEADER_LEN + max_compressedbytes(input.len()));
+    out.extend_from_slice(&(input.len() as u64).to_le_bytes());
+    out.extend_from_slice(&(chunk_len as u32).to_le_bytes());
+    for chunk in input.chunks(chunk_len) {
+        let start = out.len();
+        out.resize(start + 8 + max_compressedbytes(chunk.len()), 0);
+        // unwrap ok as the chunk holds at most u32::MAX integers and the buffer max_compressedbytes bytes
+        let bytes_written = encode_to_buf(chunk, &mut out[start + 8..]).unwrap();
+        out[start..start + 8].copy_from_slice(&(bytes_written as u64).to_le_bytes());
+        out.truncate(start + 8 + bytes_written);
+    }
+    out
+}
+
+/// Encode a sequence of u32 integers of any length, splitting it into chunks of less than `u32::MAX` integers.
+///
+/// The output stores the number of integers and is decoded with [`decode_large`].
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_large,encode_large};
+/// let out_bytes = encode_large(&[1,2,44,5123,43,534]);
+/// assert_eq!(decode_large(&out_bytes).unwrap(),vec![1,2,44,5123,43,534]);
+/// ```
+/// # Return
+///
+/// Returns the encoded chunks as a byte buffer
+///
+pub fn encode_large(input: &[u32]) -> Vec<u8> {
+    encode_chunked(input, LARGE_CHUNK_LEN)
+}
+
+/// Decode a sequence of u32 integers encoded by [`encode_large`].
+///
+/// All chunks are validated before they are decoded, so truncated or corrupted input returns an error.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_large,encode_large};
+/// let out_bytes = encode_large(&[1,2,44,5123,43,534]);
+/// assert!(decode_large(&out_bytes[..out_bytes.len()-1]).is_err());
+/// ```
+/// # Return
+///
+/// Returns the decoded integers
+///
+pub fn decode_large(input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
+    if input.len() < LARGE_HEADER_LEN {
+        return Err(StreamVbyteError::TruncatedStream(
+            input.len(),
+            LARGE_HEADER_LEN,
+        ));
+    }
+    let mut count = [0; 8];
+    count.copy_from_slice(&input[..8]);
+    let count = u64::from_le_bytes(count);
+    let chunk_len = u32::from_le_bytes([input[8], input[9], input[10], input[11]]) as u64;
+    if count > usize::MAX as u64 || (chunk_len == 0 && count > 0) {
+        return Err(StreamVbyteError::LengthTooLarge(
+            count.min(usize::MAX as u64) as usize,
+        ));
+    }
+
+    let mut output = Vec::new();
+    let mut offset = LARGE_HEADER_LEN;
+    let mut remaining = count as usize;
+    while remaining > 0 {
+        let chunk_count = remaining.min(chunk_len as usize);
+        let len = input
+            .get(offset..offset + 8)
+            .map(|b| {
+                let mut len = [0; 8];
+                len.copy_from_slice(b);
+                u64::from_le_bytes(len)
+            })
+            .ok_or(StreamVbyteError::TruncatedStream(input.len(), offset + 8))?;
+        offset += 8;
+        let payload = usize::try_from(len)
+            .ok()
+            .and_then(|len| input[offset..].get(..len))
+            .ok_or(StreamVbyteError::TruncatedStream(
+                input.len(),
+                offset.saturating_add(len.min(usize::MAX as u64) as usize),
+            ))?;
+        let start = output.len();
+        output.resize(start + chunk_count, 0);
+        Codec::Plain.decode_checked(payload, &mut output[start..], 0)?;
+        offset += payload.len();
+        remaining -= chunk_count;
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunked_roundtrip() {
+        assert_eq!(LARGE_CHUNK_LEN % 4, 0);
+        for len in [0u32, 1, 4, 11, 12, 1000] {
+            let input = crate::test_input(len);
+            for chunk_len in [4, 8, 1000, LARGE_CHUNK_LEN] {
+                let out_bytes = encode_chunked(&input, chunk_len);
+                assert_eq!(decode_large(&out_bytes).unwrap(), input);
+                for end in [0, LARGE_HEADER_LEN - 1, out_bytes.len() - 1] {
+                    if end < out_bytes.len() && len > 0 {
+                        assert!(decode_large(&out_bytes[..end]).is_err());
+                    }
+                }
+            }
+        }
+        assert_eq!(
+            encode_large(&[1, 2, 3])[LARGE_HEADER_LEN + 8..],
+            crate::encode(&[1, 2, 3])[..]
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! Helpers describing the layout of a vbyte encoded buffer.
+//!
+//! An encoded buffer of `count` integers consists of a control section of `ceil(count/4)` bytes,
+//! each holding four 2-bit length codes (lowest bits first), followed by the data section
+//! holding the little endian bytes of each value. The 0124 encoding uses the same layout but maps
+//! the length codes to 0, 1, 2 or 4 data bytes instead of 1, 2, 3 or 4.
+
+use crate::StreamVbyteError;
+
+/// The number of integers whose length codes share a control byte
+pub const BLOCK_SIZE: usize = 4;
+
+/// The maximum number of data bytes a single integer occupies
+pub const MAX_BYTES_PER_INT: usize = 4;
+
+/// The number of readable bytes the SIMD decoders of the C library require after the end of an encoding.
+///
+/// They load 16 bytes of data per block of four integers, including the last full block.
+pub(crate) const DECODE_SLACK: usize = 16;
+
+/// Returns the number of bytes of the control section of `count` encoded integers, `ceil(count / 4)`.
+///
+/// The data section of the encoding starts at this offset.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{control_bytes_for,encode};
+/// assert_eq!(control_bytes_for(0),0);
+/// assert_eq!(control_bytes_for(5),2);
+/// let out_bytes = encode(&[1,2,44,5123,43,534]);
+/// assert_eq!(out_bytes[control_bytes_for(6)],1);
+/// ```
+pub const fn control_bytes_for(count: usize) -> usize {
+    count.div_ceil(BLOCK_SIZE)
+}
+
+/// Number of data bytes each 2-bit length code occupies in the default encoding
+pub(crate) const PLAIN_CODE_LENS: [usize; 4] = [1, 2, 3, MAX_BYTES_PER_INT];
+
+/// Number of data bytes each 2-bit length code occupies in the 0124 encoding
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+pub(crate) const ZERO124_CODE_LENS: [usize; 4] = [0, 1, 2, 4];
+
+/// Number of data bytes a value with the 2-bit length `code` occupies
+pub(crate) fn code_len(code: u8) -> usize {
+    PLAIN_CODE_LENS[code as usize]
+}
+
+/// Number of data bytes occupied by the first `count` integers described by `control`
+pub(crate) fn data_len(control: &[u8], count: usize) -> usize {
+    data_len_with(control, count, &PLAIN_CODE_LENS)
+}
+
+/// Like [`data_len`] for an encoding which maps the length codes to `code_lens` bytes
+pub(crate) fn data_len_with(control: &[u8], count: usize, code_lens: &[usize; 4]) -> usize {
+    let full = count / 4;
+    let mut len: usize = control[..full]
+        .iter()
+        .map(|&key| {
+            (0..4)
+                .map(|i| code_lens[((key >> (2 * i)) & 0b11) as usize])
+                .sum::<usize>()
+        })
+        .sum();
+    for i in 0..count % 4 {
+        len += code_lens[((control[full] >> (2 * i)) & 0b11) as usize];
+    }
+    len
+}
+
+/// Number of bytes of `input` occupied by the encoding of `count` integers whose length codes map
+/// to `code_lens` data bytes
+pub(crate) fn encoded_size(
+    input: &[u8],
+    count: usize,
+    code_lens: &[usize; 4],
+) -> Result<usize, StreamVbyteError> {
+    let control_bytes = control_bytes_for(count);
+    if input.len() < control_bytes {
+        return Err(StreamVbyteError::TruncatedStream(
+            input.len(),
+            control_bytes,
+        ));
+    }
+    let total = control_bytes + data_len_with(&input[..control_bytes], count, code_lens);
+    if input.len() < total {
+        return Err(StreamVbyteError::TruncatedStream(input.len(), total));
+    }
+    Ok(total)
+}
+
+/// The number of integers whose encoding occupies exactly `input.len()` bytes.
+///
+/// Every integer adds at least one data byte, so the encoded size strictly increases with the count
+/// and at most one count matches.
+#[cfg(feature = "alloc")]
+pub(crate) fn count_for_len(input: &[u8]) -> Result<usize, StreamVbyteError> {
+    let mut count = 0;
+    let mut data = 0;
+    let mut consumed = 0;
+    while consumed < input.len() {
+        // the control byte of the next integer lies within `input` as it precedes the data section
+        data += code_len((input[count / 4] >> (2 * (count % 4))) & 0b11);
+        count += 1;
+        let next = control_bytes_for(count) + data;
+        if next > input.len() {
+            return Err(StreamVbyteError::LengthMismatch {
+                consumed,
+                total: input.len(),
+            });
+        }
+        consumed = next;
+    }
+    Ok(count)
+}
+
+/// Compute the sizes of the control and data sections of a vbyte encoded buffer of `count` integers.
+///
+/// Only the control section is inspected, the data section is never read.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode,section_sizes};
+/// let out_bytes = encode(&[1,2,44,5123,43,534]);
+/// let (control_bytes,data_bytes) = section_sizes(&out_bytes,6);
+/// assert_eq!(control_bytes,2);
+/// assert_eq!(data_bytes,8);
+/// assert_eq!(control_bytes+data_bytes,out_bytes.len());
+/// ```
+/// # Panics
+///
+/// Panics if `input` is shorter than the control section of `count` integers
+///
+/// # Return
+///
+/// Returns the tuple `(control_bytes, data_bytes)`
+///
+pub fn section_sizes(input: &[u8], count: usize) -> (usize, usize) {
+    let control_bytes = control_bytes_for(count);
+    (control_bytes, data_len(&input[..control_bytes], count))
+}
+
+/// Split a vbyte encoded buffer of `count` integers into its control and data sections without copying.
+///
+/// Unlike [`section_sizes`] this validates `input` first. Bytes beyond the encoded integers are part of
+/// neither section.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode,sections};
+/// let out_bytes = encode(&[1,2,44,5123,43,534]);
+/// let (control,data) = sections(&out_bytes,6).unwrap();
+/// assert_eq!(control,&out_bytes[..2]);
+/// assert_eq!(data,&out_bytes[2..]);
+/// assert!(sections(&out_bytes[..1],6).is_err());
+/// ```
+/// # Return
+///
+/// Returns the tuple `(control, data)` of sub-slices of `input` or [`StreamVbyteError::TruncatedStream`]
+/// if `input` is shorter than the control and data sections of `count` integers
+///
+pub fn sections(input: &[u8], count: usize) -> Result<(&[u8], &[u8]), StreamVbyteError> {
+    let total = encoded_size(input, count, &PLAIN_CODE_LENS)?;
+    Ok(input[..total].split_at(control_bytes_for(count)))
+}
+
+/// Validate that `input` holds a complete vbyte encoding of `count` integers produced by [`encode`](crate::encode).
+///
+/// Only the control section is inspected and bytes beyond the encoded integers are ignored.
+///
+/// Passing this check does not make [`decode`](crate::decode) safe to call: the SIMD decoders of the C
+/// library read up to 16 bytes past the end of the encoding. Decode untrusted input with
+/// [`decode_checked`](crate::decode_checked), which never reads past the end of `input`.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode,validate_stream};
+/// let out_bytes = encode(&[1,2,44,5123,43,534]);
+/// assert!(validate_stream(&out_bytes,6).is_ok());
+/// assert!(validate_stream(&out_bytes[..9],6).is_err());
+/// ```
+/// # Return
+///
+/// Returns [`StreamVbyteError::TruncatedStream`] if `input` is shorter than the control and data
+/// sections of `count` integers
+///
+pub fn validate_stream(input: &[u8], count: usize) -> Result<(), StreamVbyteError> {
+    encoded_size(input, count, &PLAIN_CODE_LENS).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn section_sizes_match_encoded_len() {
+        for len in 0..100u32 {
+            let input = crate::test_input(len);
+            let out_bytes = crate::encode(&input);
+            let (control_bytes, data_bytes) = super::section_sizes(&out_bytes, input.len());
+            assert_eq!(control_bytes, input.len().div_ceil(4));
+            assert_eq!(control_bytes + data_bytes, out_bytes.len());
+        }
+    }
+
+    #[test]
+    fn sections_split_encoded_buffer() {
+        use crate::StreamVbyteError;
+        for len in [0u32, 1, 2, 3, 4, 5, 1001] {
+            let input = crate::test_input(len);
+            let mut out_bytes = crate::encode(&input);
+            let (control_bytes, data_bytes) = super::section_sizes(&out_bytes, input.len());
+            let encoded_len = out_bytes.len();
+            out_bytes.extend_from_slice(&[0xff; 3]);
+            let (control, data) = super::sections(&out_bytes, input.len()).unwrap();
+            assert_eq!(control, &out_bytes[..control_bytes]);
+            assert_eq!(data, &out_bytes[control_bytes..control_bytes + data_bytes]);
+            assert_eq!(control_bytes + data_bytes, encoded_len);
+        }
+        let out_bytes = crate::encode(&[1, 2, 44, 5123, 43, 534]);
+        assert!(matches!(
+            super::sections(&out_bytes[..1], 6),
+            Err(StreamVbyteError::TruncatedStream(1, 2))
+        ));
+        assert!(matches!(
+            super::sections(&out_bytes[..9], 6),
+            Err(StreamVbyteError::TruncatedStream(9, 10))
+        ));
+    }
+
+    #[test]
+    fn validate_stream_rejects_truncated_input() {
+        use crate::StreamVbyteError;
+        let input: Vec<u32> = (0..1000).map(|v| v * 997).collect();
+        let out_bytes = crate::encode(&input);
+        assert!(super::validate_stream(&out_bytes, input.len()).is_ok());
+        assert!(super::validate_stream(&out_bytes[..0], 0).is_ok());
+
+        let err =
+            super::validate_stream(&out_bytes[..out_bytes.len() - 1], input.len()).unwrap_err();
+        assert!(matches!(
+            err,
+            StreamVbyteError::TruncatedStream(is, expected)
+                if is == out_bytes.len() - 1 && expected == out_bytes.len()
+        ));
+        let err = super::validate_stream(&out_bytes[..100], input.len()).unwrap_err();
+        assert!(matches!(err, StreamVbyteError::TruncatedStream(100, 250)));
+    }
+}
@@ -2,7 +2,8 @@
 //!
 //! Encodes 32 bit integers into variable length byte sequences in `O(n)` time.
 //!
-//! Input can be at most `u32::MAX` integers.
+//! Input can be at most `u32::MAX` integers. An empty input encodes into an empty buffer, which decodes into zero
+//! integers with every decode function.
 //!
 //! # Examples
 //!
@@ -42,28 +43,425 @@
 //! assert_eq!(&recovered,&[1,2,44,64,71,534]);
 //! ```
 //! Note: **length** of the output buf `recovered` needs to match the input length. This information needs to be stored
-//! external to compressed output.
+//! external to compressed output, or use [`encode_framed`] and [`decode_framed`] which store it in a small header:
 //!
+//! ```
+//! use streamvbyte::{decode_framed,encode_framed};
+//! let out_bytes: Vec<u8> = encode_framed(&[1,2,44,5123,43,534]);
+//! assert_eq!(decode_framed(&out_bytes).unwrap(),vec![1,2,44,5123,43,534]);
+//! ```
+//!
+//! # Features
+//!
+//! * `std` (default) - Enables the formats built on [`std::io`] and implies `alloc`. Without it the crate is `no_std`,
+//!   the -sys crate itself still links against `std`.
+//! * `alloc` - Enables the functions returning a newly allocated `Vec`, e.g. [`encode`]. The buffer based functions
+//!   [`encode_to_buf`], [`decode`] and [`decode_delta`] are always available.
+//! * `serde` - Serialization of [`CompressedU32s`].
+//! * `zstd` - A second compression pass over the vbyte encoding with zstd.
+//! * `rayon` - Multi-threaded encoding and decoding of large inputs.
+//! * `bytes` - Encoding directly into a `bytes::BufMut` with `encode_to_bufmut`.
+//! * `tracing` - `tracing` spans with the target `streamvbyte` around [`encode`], [`decode`], [`encode_delta`] and
+//!   [`decode_delta`], recording the number of integers, bytes and bytes per integer.
+//! * `bench` - Throughput measurements in `bench` for comparing the codec with others on your own hardware.
+//!
+//! # Portability
+//!
+//! The -sys crate builds the C library with cmake and the library's default `-march=native`, so the SIMD
+//! paths are chosen for the machine running the build. A binary built on a newer CPU can fail with illegal
+//! instructions on an older one. `streamvbyte-sys` 0.1 does not expose the library's `STREAMVBYTE_DISABLE_NATIVE`
+//! option yet, so portable binaries currently have to be built on the oldest CPU they are deployed to.
+//!
+//! The C library selects its SIMD path with the preprocessor and the -sys crate links a single variant, so
+//! there is nothing to choose between at runtime. Runtime dispatch would require the -sys crate to compile
+//! the scalar and SIMD variants side by side under distinct symbol names. [`active_backend`] reports
+//! the variant recorded when the crate was built.
+//!
+//! Targets the cmake build of the -sys crate does not support, such as `wasm32-unknown-unknown`, cannot
+//! build this crate either, as the -sys crate is a mandatory dependency. The pure Rust functions in
+//! [`reference`](mod@reference) do not use the C library and would be the starting point for such targets.
+//!
+//! # Format
+//!
+//! [`encode`], [`encode_delta`] and [`encode_0124`] produce exactly the bytes of `streamvbyte_encode`,
+//! `streamvbyte_delta_encode` and `streamvbyte_encode_0124` of the C library, so buffers can be exchanged
+//! with other users of the C library. This layout is a stable contract, versioned by [`FORMAT_VERSION`]:
+//!
+//! * The control section of `ceil(count / 4)` bytes comes first. Every byte holds the 2-bit length codes of
+//!   four consecutive integers, the first integer in the lowest bits. Unused codes of the final byte are zero.
+//! * The data section follows immediately and holds the little endian bytes of every integer in input order,
+//!   1, 2, 3 or 4 bytes per code, or 0, 1, 2 or 4 bytes in the 0124 encoding.
+//! * Delta encodings store the wrapping differences to the previous integer, the first one to `initial`.
+//!
+//! Neither the count nor any header is stored, the wrapper passes the input through unchanged.
+//!
+//! # Byte order
+//!
+//! The data section stores every value in little endian byte order, independent of the host. The C library
+//! copies values with `memcpy` and therefore only produces and reads this format on little endian hosts.
+//! Big endian targets such as s390x are not supported by the C functions, the pure Rust implementation in
+//! [`reference`](mod@reference) uses explicit little endian conversions and reads and writes the same bytes
+//! on every host.
+//!
+
+#![cfg_attr(not(feature = "std"), no_std)]
 
-use thiserror::Error;
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
-///! Errors that can be emitted from the streamvbyte crate and the underlying -sys crate
-#[derive(Error, Debug)]
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::fmt;
+use core::mem::MaybeUninit;
+#[cfg(feature = "alloc")]
+use core::num::NonZeroU32;
+
+/// Evaluate `$op`, returning the number of bytes encoded or decoded, within a `tracing` span named `$name`.
+///
+/// The span uses the target `streamvbyte` and records the number of integers `$len`, the bytes and the
+/// bytes per integer. Without the `tracing` feature this is just `$op`.
+macro_rules! traced {
+    ($name:literal, $len:expr, $op:expr) => {{
+        #[cfg(feature = "tracing")]
+        let span = tracing::trace_span!(
+            target: "streamvbyte",
+            $name,
+            len = $len,
+            bytes = tracing::field::Empty,
+            bytes_per_integer = tracing::field::Empty
+        )
+        .entered();
+        let bytes: usize = $op;
+        #[cfg(feature = "tracing")]
+        {
+            span.record("bytes", bytes);
+            span.record("bytes_per_integer", bytes as f64 / ($len).max(1) as f64);
+        }
+        bytes
+    }};
+}
+
+mod analysis;
+mod backend;
+#[cfg(feature = "alloc")]
+mod batch;
+#[cfg(feature = "bench")]
+pub mod bench;
+#[cfg(feature = "alloc")]
+mod blocked;
+#[cfg(feature = "bytes")]
+mod bufmut;
+#[cfg(feature = "std")]
+mod checksum;
+#[cfg(feature = "std")]
+mod chunked;
+mod codec;
+#[cfg(feature = "alloc")]
+mod compressed;
+mod delta;
+mod ffi;
+#[cfg(feature = "std")]
+mod framed;
+#[cfg(all(test, target_os = "linux"))]
+mod guard;
+#[cfg(all(test, feature = "alloc"))]
+mod interop;
+mod iter;
+#[cfg(feature = "alloc")]
+mod large;
+mod layout;
+mod narrow;
+#[cfg(feature = "std")]
+mod page;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "alloc")]
+mod patch;
+pub mod reference;
+#[cfg(feature = "alloc")]
+mod roundtrip;
+#[cfg(feature = "alloc")]
+mod scratch;
+#[cfg(feature = "std")]
+mod sequence;
+#[cfg(feature = "alloc")]
+mod shared;
+mod split;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(feature = "alloc")]
+mod transcode;
+#[cfg(feature = "alloc")]
+mod widths;
+mod zero124;
+mod zigzag;
+#[cfg(feature = "zstd")]
+mod zstd_codec;
+
+pub use analysis::{
+    control_byte_histogram, delta_bounds, encoded_delta_len, encoded_len, runs, Runs,
+};
+#[cfg(feature = "alloc")]
+pub use analysis::{recommend_mode, recommendation_report, Mode, RecommendationReport};
+pub use backend::active_backend;
+#[cfg(feature = "alloc")]
+pub use batch::{decode_batch, encode_batch};
+#[cfg(feature = "alloc")]
+pub use blocked::{encode_delta_blocked, BlockedStream};
+#[cfg(feature = "bytes")]
+pub use bufmut::encode_to_bufmut;
+#[cfg(feature = "std")]
+pub use chunked::ChunkedEncoder;
+#[cfg(feature = "alloc")]
+pub use codec::encode_with;
+pub use codec::{decode_with, Codec};
+#[cfg(feature = "alloc")]
+pub use compressed::{CompressedBuffer, CompressedU32s};
+pub use delta::{
+    decode_delta_mode, delta_differences_in_place, delta_prefix_sum_in_place, DeltaMode,
+};
+#[cfg(feature = "std")]
+pub use framed::{
+    decode_framed, decode_self_describing, decode_self_describing_with_limit, encode_best,
+    encode_framed, encode_self_describing, framed_count, framed_payload, read_frame,
+    recompress_stream, write_frame, SELF_DESCRIBING_MAX_COUNT,
+};
+#[cfg(feature = "checksum")]
+pub use framed::{decode_framed_checked, encode_framed_checked};
+#[cfg(feature = "alloc")]
+pub use iter::encode_from_iter;
+pub use iter::{decode_delta_iter, decode_iter, decode_prefix, get, DecodeCursor, DecodeIter};
+#[cfg(feature = "alloc")]
+pub use large::{decode_large, encode_large};
+#[cfg(feature = "alloc")]
+use layout::count_for_len;
+pub use layout::{
+    control_bytes_for, section_sizes, sections, validate_stream, BLOCK_SIZE, MAX_BYTES_PER_INT,
+};
+use layout::{encoded_size, PLAIN_CODE_LENS};
+pub use narrow::{decode_any, decode_u16, Encodable};
+#[cfg(feature = "alloc")]
+pub use narrow::{encode_any, encode_u16};
+#[cfg(feature = "std")]
+pub use page::{decode_page, encode_to_page, PageWriter, PAGE_HEADER_LEN, PAGE_SIZE};
+#[cfg(feature = "rayon")]
+pub use parallel::{decode_parallel, encode_parallel};
+#[cfg(feature = "alloc")]
+pub use patch::patch_in_place;
+#[cfg(feature = "alloc")]
+pub use roundtrip::{roundtrip, roundtrip_delta};
+#[cfg(feature = "alloc")]
+pub use scratch::{Decoder, Encoder};
+#[cfg(feature = "std")]
+pub use sequence::MultiBlockSequence;
+#[cfg(feature = "alloc")]
+pub use shared::SharedDecoder;
+pub use split::decode_split;
+#[cfg(feature = "alloc")]
+pub use split::{concat, encode_split};
+#[cfg(feature = "std")]
+pub use stream::{StreamVbyteReader, StreamVbyteWriter};
+#[cfg(feature = "alloc")]
+pub use transcode::{plain_from_0124, plain_to_0124, transcode_to_delta, transcode_to_plain};
+#[cfg(feature = "alloc")]
+pub use widths::encode_with_widths;
+#[cfg(feature = "alloc")]
+pub use zero124::encode_0124;
+pub use zero124::{decode_0124, encode_0124_to_buf};
+pub use zigzag::decode_delta_zigzag;
+#[cfg(feature = "alloc")]
+pub use zigzag::{decode_zigzag, encode_delta_zigzag, encode_zigzag, encode_zigzag_to_buf};
+#[cfg(feature = "zstd")]
+pub use zstd_codec::{decode_zstd, encode_zstd};
+
+/// Deterministic test input of `len` integers whose values cover all four data byte lengths
+#[cfg(test)]
+pub(crate) fn test_input(len: u32) -> Vec<u32> {
+    (0..len)
+        .map(|v| v.wrapping_mul(2654435761) >> (v % 32))
+        .collect()
+}
+
+/// Errors that can be emitted from the streamvbyte crate and the underlying -sys crate
+///
+/// Every variant carries the lengths, offsets or values required to diagnose the failure.
+#[derive(Debug)]
 pub enum StreamVbyteError {
     /// Output buffer might overflow as it is not at least max_compressedbytes long
-    #[error("insufficient output buffer len: is {0}, expected {1}")]
     OutbufOverflow(usize, usize),
+    /// A zero was decoded where only non-zero values can occur
+    UnexpectedZero(usize),
+    /// Input is shorter than required to decode the requested number of integers
+    TruncatedStream(usize, usize),
+    /// Input does not start with the expected magic byte
+    InvalidMagic(u8, u8),
+    /// Input header refers to a codec that is not known
+    UnknownCodec(u8),
+    /// Output buffer cannot hold the number of integers to decode
+    OutputLenMismatch(usize, usize),
+    /// The checksum of the input does not match the stored checksum
+    ChecksumMismatch(u32, u32),
+    /// The number of integers exceeds the supported maximum of `u32::MAX` or a caller supplied limit
+    LengthTooLarge(usize),
+    /// The input of a delta encoding decreases at `index`, or its first value is smaller than the initial value
+    NotMonotone { index: usize },
+    /// The prefix sum of a delta encoding exceeds `u32::MAX` at `index`
+    DeltaOverflow { index: usize },
+    /// Decoding consumed `consumed` bytes of an input that is `total` bytes long
+    LengthMismatch { consumed: usize, total: usize },
+    /// The `initial` value of a delta encoding exceeds the `first` integer of the input
+    InvalidInitial { initial: u32, first: u32 },
+    /// The `index` exceeds the `count` integers of the input
+    IndexOutOfBounds { index: usize, count: usize },
+    /// The byte width supplied for the integer at `index` is outside of `1..=4` or cannot hold its value
+    InsufficientWidth { index: usize, width: u8 },
+    /// The decoded `value` at `index` does not fit the integer type decoded into
+    ValueTooLarge { index: usize, value: u32 },
+    /// Decoding the encoding of an input restored `actual` instead of `expected` at `index`
+    RoundtripMismatch {
+        index: usize,
+        expected: u32,
+        actual: u32,
+    },
+    /// The zstd pass of [`decode_zstd`] failed
+    #[cfg(feature = "zstd")]
+    Zstd(std::io::Error),
 }
 
-///! Returns the maximum number of bytes required by the compressor to encode `length` u32s
-pub fn max_compressedbytes(length: usize) -> usize {
+impl fmt::Display for StreamVbyteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamVbyteError::OutbufOverflow(is, expected) => {
+                write!(
+                    f,
+                    "insufficient output buffer len: is {is}, expected {expected}"
+                )
+            }
+            StreamVbyteError::UnexpectedZero(index) => {
+                write!(f, "unexpected zero value decoded at index {index}")
+            }
+            StreamVbyteError::TruncatedStream(is, expected) => write!(
+                f,
+                "truncated input stream: is {is} bytes, expected at least {expected}"
+            ),
+            StreamVbyteError::InvalidMagic(is, expected) => {
+                write!(
+                    f,
+                    "invalid magic byte: is {is:#04x}, expected {expected:#04x}"
+                )
+            }
+            StreamVbyteError::UnknownCodec(tag) => write!(f, "unknown codec tag {tag}"),
+            StreamVbyteError::OutputLenMismatch(is, expected) => {
+                write!(
+                    f,
+                    "insufficient output len: is {is}, expected at least {expected}"
+                )
+            }
+            StreamVbyteError::ChecksumMismatch(is, expected) => {
+                write!(
+                    f,
+                    "checksum mismatch: is {is:#010x}, expected {expected:#010x}"
+                )
+            }
+            StreamVbyteError::LengthTooLarge(len) => write!(
+                f,
+                "length too large: {len} exceeds the maximum number of integers"
+            ),
+            StreamVbyteError::NotMonotone { index } => {
+                write!(f, "input is not non decreasing at index {index}")
+            }
+            StreamVbyteError::DeltaOverflow { index } => {
+                write!(f, "delta prefix sum overflows u32 at index {index}")
+            }
+            StreamVbyteError::LengthMismatch { consumed, total } => write!(
+                f,
+                "length mismatch: decoding consumed {consumed} of {total} input bytes"
+            ),
+            StreamVbyteError::InvalidInitial { initial, first } => write!(
+                f,
+                "invalid initial value {initial}: exceeds the first integer {first}"
+            ),
+            StreamVbyteError::IndexOutOfBounds { index, count } => {
+                write!(f, "index {index} out of bounds for {count} integers")
+            }
+            StreamVbyteError::InsufficientWidth { index, width } => write!(
+                f,
+                "insufficient width of {width} bytes for the integer at index {index}"
+            ),
+            StreamVbyteError::ValueTooLarge { index, value } => write!(
+                f,
+                "value {value} at index {index} does not fit the output integer type"
+            ),
+            StreamVbyteError::RoundtripMismatch {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "roundtrip mismatch at index {index}: decoded {actual}, expected {expected}"
+            ),
+            #[cfg(feature = "zstd")]
+            StreamVbyteError::Zstd(e) => write!(f, "zstd decompression failed: {e}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StreamVbyteError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "zstd")]
+            StreamVbyteError::Zstd(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// The version of the byte format produced by [`encode`] and [`encode_delta`].
+///
+/// The version is only increased if the encoded bytes of any input change, so data persisted with an
+/// equal version can always be decoded.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Returns the maximum number of bytes required by the compressor to encode `length` u32s
+///
+/// The result overflows for lengths close to `usize::MAX / 4`, use [`try_max_compressedbytes`] for lengths
+/// which are not known to be valid.
+///
+/// Mirrors `streamvbyte_max_compressedbytes` of the C library, which is a `static inline` function in
+/// its header and therefore has no symbol the -sys crate could bind.
+pub const fn max_compressedbytes(length: usize) -> usize {
     // number of control bytes:
-    let cb = (length + 3) / 4;
-    // maximum number of control bytes:
-    let db = length * std::mem::size_of::<u32>();
+    let cb = control_bytes_for(length);
+    // maximum number of data bytes:
+    let db = length * MAX_BYTES_PER_INT;
     cb + db
 }
 
+/// Returns the maximum number of bytes required by the compressor to encode `length` u32s using checked arithmetic.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{max_compressedbytes,try_max_compressedbytes};
+/// assert_eq!(try_max_compressedbytes(6).unwrap(),max_compressedbytes(6));
+/// assert!(try_max_compressedbytes(usize::MAX).is_err());
+/// ```
+/// # Return
+///
+/// Returns the maximum number of bytes or [`StreamVbyteError::LengthTooLarge`] if `length` exceeds `u32::MAX`
+/// or the result does not fit into a usize
+///
+pub fn try_max_compressedbytes(length: usize) -> Result<usize, StreamVbyteError> {
+    if length > u32::MAX as usize {
+        return Err(StreamVbyteError::LengthTooLarge(length));
+    }
+    length
+        .checked_mul(MAX_BYTES_PER_INT)
+        .and_then(|db| db.checked_add(control_bytes_for(length)))
+        .ok_or(StreamVbyteError::LengthTooLarge(length))
+}
+
 /// Encode a sequence of u32 integers into a vbyte encoded byte representation.
 /// Internally a buffer of length [`max_compressedbytes`] is allocated to store the compressed result.
 ///
@@ -75,21 +473,116 @@ pub fn max_compressedbytes(length: usize) -> usize {
 /// use streamvbyte::encode;
 /// let out_bytes: Vec<u8> = encode(&[1,2,44,5123,43,534]);
 /// ```
+/// # Panics
+///
+/// Panics if `input` holds more than `u32::MAX` integers
+///
 /// # Return
 ///
 /// Returns the encoded output as a byte buffer
 ///
+#[cfg(feature = "alloc")]
 pub fn encode(input: &[u32]) -> Vec<u8> {
-    let output_bytes_req = max_compressedbytes(input.len()) as usize;
-    let mut buf = vec![0; output_bytes_req];
-    // SAFETY: unwrap ok as we compute required max bytes beforehand
-    let bytes_written = encode_to_buf(input, &mut buf).unwrap();
+    try_encode(input).expect("at most u32::MAX integers can be encoded")
+}
+
+/// Encode a sequence of u32 integers like [`encode`], returning an error instead of panicking for oversized input.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode,try_encode};
+/// let out_bytes = try_encode(&[1,2,44,5123,43,534]).unwrap();
+/// assert_eq!(out_bytes,encode(&[1,2,44,5123,43,534]));
+/// ```
+/// # Return
+///
+/// Returns the encoded output as a byte buffer or [`StreamVbyteError::LengthTooLarge`] if `input` holds more than
+/// `u32::MAX` integers or its [`max_compressedbytes`] overflow `usize`
+///
+#[cfg(feature = "alloc")]
+pub fn try_encode(input: &[u32]) -> Result<Vec<u8>, StreamVbyteError> {
+    let mut buf = vec![0; try_max_compressedbytes(input.len())?];
+    let bytes_written = traced!("encode", input.len(), encode_to_buf(input, &mut buf)?);
     buf.truncate(bytes_written);
-    buf
+    Ok(buf)
+}
+
+/// Encode a sequence of u32 integers like [`encode`] and append the result to `output`.
+///
+/// The existing contents of `output` are left untouched and the integers are encoded directly into its
+/// spare capacity, so many segments can be accumulated in a single buffer.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode,encode_onto};
+/// let mut out_bytes = Vec::new();
+/// let first = encode_onto(&[1,2,44],&mut out_bytes);
+/// let second = encode_onto(&[5123,43,534],&mut out_bytes);
+/// assert_eq!(&out_bytes[..first],&encode(&[1,2,44])[..]);
+/// assert_eq!(&out_bytes[first..],&encode(&[5123,43,534])[..]);
+/// assert_eq!(first+second,out_bytes.len());
+/// ```
+/// # Panics
+///
+/// Panics if `input` holds more than `u32::MAX` integers
+///
+/// # Return
+///
+/// Returns the number of bytes appended to `output`
+///
+#[cfg(feature = "alloc")]
+pub fn encode_onto(input: &[u32], output: &mut Vec<u8>) -> usize {
+    let output_bytes_req =
+        try_max_compressedbytes(input.len()).expect("at most u32::MAX integers can be encoded");
+    output.reserve(output_bytes_req);
+    let len = output.len();
+    // SAFETY: the spare capacity holds max compressed size bytes, of which the encoder initializes the returned amount
+    unsafe {
+        let bytes_written = streamvbyte_sys::streamvbyte_encode(
+            input.as_ptr(),
+            input.len() as u32,
+            output.spare_capacity_mut().as_mut_ptr() as *mut u8,
+        ) as usize;
+        output.set_len(len + bytes_written);
+        bytes_written
+    }
+}
+
+/// Encode a sequence of u32 integers like [`encode`] and additionally return the largest value of the sequence.
+///
+/// The maximum is tracked while encoding with [`encode_from_iter`], so `input` is only scanned once.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::encode_with_max;
+/// let (out_bytes,max) = encode_with_max(&[1,2,44,5123,43,534]);
+/// assert_eq!(out_bytes.len(),10);
+/// assert_eq!(max,5123);
+/// ```
+/// # Return
+///
+/// Returns the encoded output as a byte buffer and the maximum value of `input` (`0` for an empty input)
+///
+#[cfg(feature = "alloc")]
+pub fn encode_with_max(input: &[u32]) -> (Vec<u8>, u32) {
+    let mut max = 0;
+    let (out_bytes, _) = encode_from_iter(input.iter().map(|&v| {
+        max = max.max(v);
+        v
+    }));
+    (out_bytes, max)
 }
 
 /// Decode a sequence of u32 integers from a vbyte encoded byte representation into an existing buffer `output`.
 ///
+/// `input` is not bounds checked, use [`decode_checked`] or [`validate_stream`] for untrusted input.
+///
+/// `input` has no alignment requirement. The C library reads the data section with `memcpy` and unaligned
+/// SIMD loads, so slices at any offset, e.g. into a memory mapped file, can be decoded without copying.
+///
 /// # Arguments
 ///
 /// * `input` - The input sequence of vbyte encoding (u8s)
@@ -106,164 +599,916 @@ pub fn encode(input: &[u32]) -> Vec<u8> {
 /// assert_eq!(bytes_read,out_buf.len());
 /// ```
 ///
+/// # Panics
+///
+/// Panics if `output` holds more than `u32::MAX` integers
+///
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding
+///
+pub fn decode(input: &[u8], output: &mut [u32]) -> usize {
+    assert!(
+        output.len() <= u32::MAX as usize,
+        "at most u32::MAX integers can be decoded"
+    );
+    traced!("decode", output.len(), unsafe {
+        streamvbyte_sys::streamvbyte_decode(
+            input.as_ptr(),
+            output.as_mut_ptr(),
+            output.len() as u32,
+        ) as usize
+    })
+}
+
+/// Decode a sequence of u32 integers like [`decode`] after validating that `input` holds `output.len()` integers.
+///
+/// The control section is scanned for the exact length of the data section before the C decoder runs.
+/// The SIMD decoders read up to 16 bytes past the end of the encoding, so an encoding which ends closer
+/// than that to the end of `input` is decoded from a padded copy. `input` is never read past its end,
+/// which makes this safe to call on untrusted input. The `decode_checked` target in `fuzz/` exercises
+/// this with arbitrary bytes and counts.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_checked,encode};
+/// let out_buf = encode(&[1,2,44,5123,43,534]);
+/// let mut recovered = vec![0;6];
+/// let bytes_read = decode_checked(&out_buf,&mut recovered).unwrap();
+/// assert_eq!(bytes_read,out_buf.len());
+///
+/// let mut too_many = vec![0;12];
+/// assert!(decode_checked(&out_buf,&mut too_many).is_err());
+/// ```
+///
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding or
+/// [`StreamVbyteError::TruncatedStream`] if `input` is too short
+///
+pub fn decode_checked(input: &[u8], output: &mut [u32]) -> Result<usize, StreamVbyteError> {
+    Codec::Plain.decode_checked(input, output, 0)
+}
+
+/// Alias of [`decode_checked`], pairing with [`try_encode`].
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{try_decode,try_encode};
+/// let out_buf = try_encode(&[1,2,44,5123,43,534]).unwrap();
+/// let mut recovered = vec![0;6];
+/// assert_eq!(try_decode(&out_buf,&mut recovered).unwrap(),out_buf.len());
+/// assert_eq!(recovered,vec![1,2,44,5123,43,534]);
+/// assert!(try_decode(&out_buf[..9],&mut recovered).is_err());
+/// ```
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding or
+/// [`StreamVbyteError::TruncatedStream`] if `input` is too short
+///
+pub fn try_decode(input: &[u8], output: &mut [u32]) -> Result<usize, StreamVbyteError> {
+    decode_checked(input, output)
+}
+
+/// Decode a sequence of u32 integers like [`decode_checked`] and verify that `input` is consumed exactly.
+///
+/// When `input` holds precisely one encoded stream, an `output` buffer of the wrong length is detected
+/// instead of silently decoding too few or too many integers.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_exact,encode,StreamVbyteError};
+/// let out_buf = encode(&[1,2,44,5123,43,534]);
+/// let mut recovered = vec![0;6];
+/// assert!(decode_exact(&out_buf,&mut recovered).is_ok());
+/// assert_eq!(recovered,vec![1,2,44,5123,43,534]);
+///
+/// let mut too_few = vec![0;5];
+/// assert!(matches!(
+///     decode_exact(&out_buf,&mut too_few),
+///     Err(StreamVbyteError::LengthMismatch { consumed: 8, total: 10 })
+/// ));
+/// ```
+///
+/// # Return
+///
+/// Returns [`StreamVbyteError::TruncatedStream`] if `input` is too short and
+/// [`StreamVbyteError::LengthMismatch`] if bytes remain after decoding `output.len()` integers
+///
+pub fn decode_exact(input: &[u8], output: &mut [u32]) -> Result<(), StreamVbyteError> {
+    let consumed = encoded_size(input, output.len(), &PLAIN_CODE_LENS)?;
+    if consumed != input.len() {
+        return Err(StreamVbyteError::LengthMismatch {
+            consumed,
+            total: input.len(),
+        });
+    }
+    Codec::Plain.decode_checked(input, output, 0)?;
+    Ok(())
+}
+
+/// Decode column `k` of concatenated encodings of several columns of u32 integers into an existing buffer `output`.
+///
+/// The encodings of columns `0..k` are skipped by summing the lengths described by their control sections,
+/// their data sections are never read. Every column up to `k` is validated, so the function is safe to
+/// call on untrusted input.
+///
+/// # Arguments
+///
+/// * `input` - The concatenated vbyte encodings (u8s) of all columns
+/// * `counts` - The number of integers of each column
+/// * `k` - The index of the column to decode
+/// * `output` - The output buf to store the recovered integers of column `k`
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_nth_column,encode};
+/// let mut input = encode(&[1,2,44]);
+/// input.extend(encode(&[5123,43,534,7]));
+/// let mut recovered = vec![0;4];
+/// assert_eq!(decode_nth_column(&input,&[3,4],1,&mut recovered).unwrap(),7);
+/// assert_eq!(recovered,vec![5123,43,534,7]);
+/// assert!(decode_nth_column(&input[..9],&[3,4],1,&mut recovered).is_err());
+/// ```
+/// # Return
+///
+/// Returns the number of bytes processed from the encoding of column `k` during decoding,
+/// [`StreamVbyteError::IndexOutOfBounds`] if `k` is not smaller than `counts.len()`,
+/// [`StreamVbyteError::OutputLenMismatch`] if the length of `output` differs from `counts[k]` or
+/// [`StreamVbyteError::TruncatedStream`] if `input` is too short to hold the columns `0..=k`
+///
+pub fn decode_nth_column(
+    input: &[u8],
+    counts: &[usize],
+    k: usize,
+    output: &mut [u32],
+) -> Result<usize, StreamVbyteError> {
+    if k >= counts.len() {
+        return Err(StreamVbyteError::IndexOutOfBounds {
+            index: k,
+            count: counts.len(),
+        });
+    }
+    if output.len() != counts[k] {
+        return Err(StreamVbyteError::OutputLenMismatch(output.len(), counts[k]));
+    }
+    let mut offset = 0;
+    for &count in &counts[..k] {
+        offset += encoded_size(&input[offset..], count, &PLAIN_CODE_LENS)?;
+    }
+    Codec::Plain.decode_checked(&input[offset..], output, 0)
+}
+
+/// Decode a sequence of u32 integers like [`decode`] into possibly uninitialized memory.
+///
+/// Every element of `output` is initialized once the function returns, so the caller can assume the
+/// whole slice to be initialized, e.g. by calling `set_len` on a `Vec` created with `Vec::with_capacity`.
+///
+/// `input` is not bounds checked, use [`validate_stream`] for untrusted input.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_uninit,encode};
+/// let out_buf = encode(&[1,2,44,5123,43,534]);
+/// let mut recovered: Vec<u32> = Vec::with_capacity(6);
+/// let bytes_read = decode_uninit(&out_buf,&mut recovered.spare_capacity_mut()[..6]);
+/// // SAFETY: decode_uninit initialized the first 6 elements
+/// unsafe { recovered.set_len(6) };
+/// assert_eq!(bytes_read,out_buf.len());
+/// assert_eq!(recovered,vec![1,2,44,5123,43,534]);
+/// ```
+/// # Panics
+///
+/// Panics if `output` holds more than `u32::MAX` integers
+///
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding
+///
+pub fn decode_uninit(input: &[u8], output: &mut [MaybeUninit<u32>]) -> usize {
+    assert!(
+        output.len() <= u32::MAX as usize,
+        "at most u32::MAX integers can be decoded"
+    );
+    // SAFETY: MaybeUninit<u32> has the layout of u32 and the decoder writes all `output.len()` integers
+    unsafe {
+        streamvbyte_sys::streamvbyte_decode(
+            input.as_ptr(),
+            output.as_mut_ptr() as *mut u32,
+            output.len() as u32,
+        ) as usize
+    }
+}
+
+/// Decode `count` u32 integers from a vbyte encoded byte representation into a new buffer.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_to_vec,encode};
+/// let out_buf = encode(&[1,2,44,5123,43,534]);
+/// let recovered = decode_to_vec(&out_buf,6);
+/// assert_eq!(recovered,vec![1,2,44,5123,43,534]);
+/// ```
+///
+/// # Return
+///
+/// Returns the recovered integers
+///
+#[cfg(feature = "alloc")]
+pub fn decode_to_vec(input: &[u8], count: usize) -> Vec<u32> {
+    let mut output = vec![0; count];
+    decode(input, &mut output);
+    output
+}
+
+/// Decode all u32 integers of a buffer holding exactly one encoding produced by [`encode`], without knowing their count.
+///
+/// The count is derived from the control section as the number of integers whose encoding occupies
+/// exactly `input.len()` bytes. This requires `input` to hold a single stream without any trailing
+/// bytes. Note that the derived count can differ from the original one if `input` is not such a buffer.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_all,encode};
+/// let out_buf = encode(&[1,2,44,5123,43,534]);
+/// assert_eq!(decode_all(&out_buf).unwrap(),vec![1,2,44,5123,43,534]);
+/// assert!(decode_all(&out_buf[..9]).is_err());
+/// ```
+///
+/// # Return
+///
+/// Returns the recovered integers or [`StreamVbyteError::LengthMismatch`] if no number of integers is encoded
+/// in exactly `input.len()` bytes
+///
+#[cfg(feature = "alloc")]
+pub fn decode_all(input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
+    let count = count_for_len(input)?;
+    let mut output = vec![0; count];
+    Codec::Plain.decode_checked(input, &mut output, 0)?;
+    Ok(output)
+}
+
+/// Decode `count` u32 integers from a vbyte encoded byte representation and append them to `output`.
+///
+/// The existing contents of `output` are left untouched and the new integers are decoded directly into
+/// its spare capacity.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_append,encode};
+/// let mut recovered = Vec::new();
+/// decode_append(&encode(&[1,2,44]),3,&mut recovered);
+/// let bytes_read = decode_append(&encode(&[5123,43,534]),3,&mut recovered);
+/// assert_eq!(bytes_read,6);
+/// assert_eq!(recovered,vec![1,2,44,5123,43,534]);
+/// ```
+/// # Panics
+///
+/// Panics if `count` exceeds `u32::MAX`
+///
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding
+///
+#[cfg(feature = "alloc")]
+pub fn decode_append(input: &[u8], count: usize, output: &mut Vec<u32>) -> usize {
+    output.reserve(count);
+    let len = output.len();
+    let bytes_read = decode_uninit(input, &mut output.spare_capacity_mut()[..count]);
+    // SAFETY: decode_uninit initialized the `count` integers following the existing contents
+    unsafe { output.set_len(len + count) };
+    bytes_read
+}
+
+/// Decode `count` u32 integers into `output`, replacing its contents and reusing its allocation.
+///
+/// Unlike [`decode_to_vec`] no new buffer is allocated once `output` has grown to the largest count, so a single
+/// buffer can be reused when decoding many segments in a loop.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_into_vec,encode};
+/// let mut recovered = Vec::new();
+/// for segment in [vec![1,2,44,5123],vec![43,534]] {
+///     let bytes_read = decode_into_vec(&encode(&segment),segment.len(),&mut recovered);
+///     assert_eq!(recovered,segment);
+///     assert_eq!(bytes_read,encode(&segment).len());
+/// }
+/// ```
+/// # Panics
+///
+/// Panics if `count` exceeds `u32::MAX`
+///
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding
+///
+#[cfg(feature = "alloc")]
+pub fn decode_into_vec(input: &[u8], count: usize, output: &mut Vec<u32>) -> usize {
+    output.clear();
+    decode_append(input, count, output)
+}
+
+/// Decode `count` u32 integers from a vbyte encoded byte representation into the fixed size array `output`.
+///
+/// Unlike [`decode`] the number of encoded integers `count` has to be known, e.g. from an external length store or
+/// a framed header, and the recovered integers are stored in `output[..count]`.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_into_array,encode};
+/// let out_buf = encode(&[1,2,44,5123,43,534]);
+/// let mut recovered = [0u32;8];
+/// let bytes_read = decode_into_array(&out_buf,&mut recovered,6).unwrap();
+/// assert_eq!(bytes_read,out_buf.len());
+/// assert_eq!(&recovered[..6],&[1,2,44,5123,43,534]);
+///
+/// let mut too_small = [0u32;4];
+/// assert!(decode_into_array(&out_buf,&mut too_small,6).is_err());
+/// ```
+///
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding or
+/// [`StreamVbyteError::OutputLenMismatch`] if `count` exceeds `N`
+///
+pub fn decode_into_array<const N: usize>(
+    input: &[u8],
+    output: &mut [u32; N],
+    count: usize,
+) -> Result<usize, StreamVbyteError> {
+    if count > N {
+        return Err(StreamVbyteError::OutputLenMismatch(N, count));
+    }
+    Ok(decode(input, &mut output[..count]))
+}
+
+/// Encode a sequence of u32 integers into a vbyte encoded byte representation into an existing buffer `output`.
+///
+/// Required: output buf is at least [`max_compressedbytes`] long.
+///
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{max_compressedbytes,encode_to_buf};
+/// let input = vec![1,2,44,5123,43,534];
+/// let max_bytes = max_compressedbytes(input.len());
+/// let mut out_buf = vec![0;max_bytes];
+/// let bytes_written = encode_to_buf(&input,&mut out_buf);
+/// assert_eq!(bytes_written.unwrap(),10);
+/// ```
+/// # Return
+///
+/// Returns the number of bytes written to output during encoding or [`StreamVbyteError::LengthTooLarge`]
+/// if `input` holds more than `u32::MAX` integers
+///
+pub fn encode_to_buf(input: &[u32], output: &mut [u8]) -> Result<usize, StreamVbyteError> {
+    let output_bytes_req = try_max_compressedbytes(input.len())?;
+    if output.len() < output_bytes_req {
+        return Err(StreamVbyteError::OutbufOverflow(
+            output.len(),
+            output_bytes_req,
+        ));
+    }
+    // SAFETY: output buf is as long as max compressed size
+    unsafe {
+        Ok(streamvbyte_sys::streamvbyte_encode(
+            input.as_ptr(),
+            input.len() as u32,
+            output.as_mut_ptr(),
+        ) as usize)
+    }
+}
+
+/// Encode a sequence **non decreasing** of u32 integers into a vbyte encoded byte representation.
+/// Internally a buffer of length [`max_compressedbytes`] is allocated to store the compressed result.
+///
+/// The buffer will be truncated to the correct length before returning.
+///
+/// # Arguments
+///
+/// * `input` - The input sequence of non decreasing u32 integers
+/// * `initial` - The intial value to substract from the all the value in the array to decrease the universe. **MUST** be <= input\[0\]
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::encode_delta;
+/// let out_bytes: Vec<u8> = encode_delta(&[1,2,44,5123,43,534],1);
+/// ```
+/// # Panics
+///
+/// Panics if `input` holds more than `u32::MAX` integers
+///
+/// # Return
+///
+/// Returns the encoded output as a byte buffer
+///
+#[cfg(feature = "alloc")]
+pub fn encode_delta(input: &[u32], intial: u32) -> Vec<u8> {
+    try_encode_delta(input, intial).expect("at most u32::MAX integers can be encoded")
+}
+
+/// Encode a sequence of u32 integers like [`encode_delta`], returning an error instead of panicking for oversized input.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode_delta,try_encode_delta};
+/// let out_bytes = try_encode_delta(&[1,2,44,64,71,534],1).unwrap();
+/// assert_eq!(out_bytes,encode_delta(&[1,2,44,64,71,534],1));
+/// ```
+/// # Return
+///
+/// Returns the encoded output as a byte buffer or [`StreamVbyteError::LengthTooLarge`] if `input` holds more than
+/// `u32::MAX` integers or its [`max_compressedbytes`] overflow `usize`
+///
+#[cfg(feature = "alloc")]
+pub fn try_encode_delta(input: &[u32], initial: u32) -> Result<Vec<u8>, StreamVbyteError> {
+    let mut buf = vec![0; try_max_compressedbytes(input.len())?];
+    let bytes_written = traced!(
+        "encode_delta",
+        input.len(),
+        encode_delta_to_buf(input, &mut buf, initial)?
+    );
+    buf.truncate(bytes_written);
+    Ok(buf)
+}
+
+/// Encode a sequence of u32 integers like [`encode_delta`] after verifying that it is **non decreasing**.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode_delta,encode_delta_checked,StreamVbyteError};
+/// let out_bytes = encode_delta_checked(&[1,2,44,64,71,534],1).unwrap();
+/// assert_eq!(out_bytes,encode_delta(&[1,2,44,64,71,534],1));
+/// assert!(matches!(
+///     encode_delta_checked(&[1,2,44,5123,43,534],1),
+///     Err(StreamVbyteError::NotMonotone { index: 4 })
+/// ));
+/// ```
+/// # Return
+///
+/// Returns the encoded output as a byte buffer, [`StreamVbyteError::NotMonotone`] with the index of the first
+/// value smaller than its predecessor or `initial`, or [`StreamVbyteError::LengthTooLarge`] if `input` holds
+/// more than `u32::MAX` integers
+///
+#[cfg(feature = "alloc")]
+pub fn encode_delta_checked(input: &[u32], initial: u32) -> Result<Vec<u8>, StreamVbyteError> {
+    let mut prev = initial;
+    for (index, &value) in input.iter().enumerate() {
+        if value < prev {
+            return Err(StreamVbyteError::NotMonotone { index });
+        }
+        prev = value;
+    }
+    try_encode_delta(input, initial)
+}
+
+/// Encode a sequence of u32 integers like [`encode_delta`] after verifying that `initial` does not exceed the first integer.
+///
+/// A first integer below `initial` wraps around to a gap of nearly `u32::MAX`, which occupies four data bytes and is
+/// reported as an overflow by [`decode_delta_mode`] with [`DeltaMode::Checked`]. Unlike [`encode_delta_checked`]
+/// only this precondition is checked, the remaining integers are not required to be non decreasing.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode_delta,encode_delta_strict,StreamVbyteError};
+/// let out_bytes = encode_delta_strict(&[1,2,44,64,71,534],1).unwrap();
+/// assert_eq!(out_bytes,encode_delta(&[1,2,44,64,71,534],1));
+/// assert!(matches!(
+///     encode_delta_strict(&[1,2,44,64,71,534],2),
+///     Err(StreamVbyteError::InvalidInitial { initial: 2, first: 1 })
+/// ));
+/// ```
+/// # Return
+///
+/// Returns the encoded output as a byte buffer, [`StreamVbyteError::InvalidInitial`] if `initial` exceeds the
+/// first integer of a non empty `input`, or [`StreamVbyteError::LengthTooLarge`] if `input` holds more than
+/// `u32::MAX` integers
+///
+#[cfg(feature = "alloc")]
+pub fn encode_delta_strict(input: &[u32], initial: u32) -> Result<Vec<u8>, StreamVbyteError> {
+    if let Some(&first) = input.first().filter(|&&first| initial > first) {
+        return Err(StreamVbyteError::InvalidInitial { initial, first });
+    }
+    try_encode_delta(input, initial)
+}
+
+/// Encode a sequence **non decreasing** of u32 integers into a vbyte encoded byte representation into an existing buffer `output`.
+///
+/// # Arguments
+///
+/// * `input` - The input sequence of non decreasing u32 integers
+/// * `output` - The output u8 slice of at least  [`max_compressedbytes`]
+/// * `initial` - The intial value to substract from the all the value in the array to decrease the universe. **MUST** be <= input\[0\]
+///
+/// Required: output buf is at least [`max_compressedbytes`] long.
+///
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{max_compressedbytes,encode_delta_to_buf};
+/// let input = vec![1,2,44,54,433,534];
+/// let max_bytes = max_compressedbytes(input.len());
+/// let mut out_buf = vec![0;max_bytes];
+/// let bytes_written = encode_delta_to_buf(&input,&mut out_buf,0);
+/// assert_eq!(bytes_written.unwrap(),9);
+/// ```
+/// # Return
+///
+/// Returns the number of bytes written to output during encoding or [`StreamVbyteError::LengthTooLarge`]
+/// if `input` holds more than `u32::MAX` integers
+///
+pub fn encode_delta_to_buf(
+    input: &[u32],
+    output: &mut [u8],
+    initial: u32,
+) -> Result<usize, StreamVbyteError> {
+    let output_bytes_req = try_max_compressedbytes(input.len())?;
+    if output.len() < output_bytes_req {
+        return Err(StreamVbyteError::OutbufOverflow(
+            output.len(),
+            output_bytes_req,
+        ));
+    }
+    unsafe {
+        Ok(streamvbyte_sys::streamvbyte_delta_encode(
+            input.as_ptr(),
+            input.len() as u32,
+            output.as_mut_ptr(),
+            initial,
+        ) as usize)
+    }
+}
+
+/// Decode a sequence of non decreasing u32 integers from a vbyte encoded byte representation into an existing buffer `output`.
+///
+/// # Arguments
+///
+/// * `input` - The input sequence of vbyte encoding (u8s)
+/// * `output` - The output buf to store the recovered non decreasing u32 integers. **MUST** be the same size as the original input sequence
+/// * `initial` - The intial value thaw was substract from the all the value in the array during encoding.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{max_compressedbytes,encode_delta,decode_delta};
+/// let input = vec![1,2,44,5123,43,534];
+/// let out_buf = encode_delta(&input,1);
+/// let mut recovered = vec![0;6];
+/// let bytes_read = decode_delta(&out_buf,&mut recovered,1);
+/// assert_eq!(bytes_read,out_buf.len());
+/// ```
+/// # Panics
+///
+/// Panics if `output` holds more than `u32::MAX` integers
+///
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding
+///
+pub fn decode_delta(input: &[u8], output: &mut [u32], initial: u32) -> usize {
+    assert!(
+        output.len() <= u32::MAX as usize,
+        "at most u32::MAX integers can be decoded"
+    );
+    traced!("decode_delta", output.len(), unsafe {
+        streamvbyte_sys::streamvbyte_delta_decode(
+            input.as_ptr(),
+            output.as_mut_ptr(),
+            output.len() as u32,
+            initial,
+        ) as usize
+    })
+}
+
+/// Decode a sequence of non decreasing u32 integers like [`decode_delta`] after validating that `input` holds `output.len()` integers.
+///
+/// This is the counterpart of [`decode_checked`] for delta encoded input. Like it, `input` is never read
+/// past its end, which makes this safe to call on untrusted input. Prefix sums exceeding `u32::MAX` wrap around like in [`decode_delta`], use
+/// [`decode_delta_mode`] with [`DeltaMode::Checked`] to detect them.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_delta_checked,encode_delta};
+/// let out_buf = encode_delta(&[1,2,44,64,71,534],1);
+/// let mut recovered = vec![0;6];
+/// assert_eq!(decode_delta_checked(&out_buf,&mut recovered,1).unwrap(),out_buf.len());
+/// assert_eq!(recovered,vec![1,2,44,64,71,534]);
+/// assert!(decode_delta_checked(&out_buf[..7],&mut recovered,1).is_err());
+/// ```
+///
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding or
+/// [`StreamVbyteError::TruncatedStream`] if `input` is too short
+///
+pub fn decode_delta_checked(
+    input: &[u8],
+    output: &mut [u32],
+    initial: u32,
+) -> Result<usize, StreamVbyteError> {
+    Codec::Delta.decode_checked(input, output, initial)
+}
+
+/// Alias of [`decode_delta_checked`], pairing with [`try_encode_delta`].
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{try_decode_delta,try_encode_delta};
+/// let out_buf = try_encode_delta(&[1,2,44,64,71,534],1).unwrap();
+/// let mut recovered = vec![0;6];
+/// assert_eq!(try_decode_delta(&out_buf,&mut recovered,1).unwrap(),out_buf.len());
+/// assert_eq!(recovered,vec![1,2,44,64,71,534]);
+/// ```
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding or
+/// [`StreamVbyteError::TruncatedStream`] if `input` is too short
+///
+pub fn try_decode_delta(
+    input: &[u8],
+    output: &mut [u32],
+    initial: u32,
+) -> Result<usize, StreamVbyteError> {
+    decode_delta_checked(input, output, initial)
+}
+
+/// Decode a sequence of non decreasing u32 integers like [`decode_delta`] and additionally return the last decoded value.
+///
+/// Passing the returned value as `initial` of the next segment reconstructs a continuous non decreasing
+/// sequence across segment boundaries, provided every segment was encoded with the last value of the
+/// previous segment as its initial value.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_delta_chained,encode_delta};
+/// let first = encode_delta(&[1,2,44],0);
+/// let second = encode_delta(&[64,71,534],44);
+/// let mut recovered = vec![0;6];
+/// let (_,last) = decode_delta_chained(&first,&mut recovered[..3],0);
+/// let (_,last) = decode_delta_chained(&second,&mut recovered[3..],last);
+/// assert_eq!(last,534);
+/// assert_eq!(recovered,vec![1,2,44,64,71,534]);
+///
+/// assert_eq!(decode_delta_chained(&[],&mut [],7),(0,7));
+/// ```
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding and the last decoded value, which is
+/// `initial` if `output` is empty
+///
+pub fn decode_delta_chained(input: &[u8], output: &mut [u32], initial: u32) -> (usize, u32) {
+    let bytes_read = decode_delta(input, output, initial);
+    (bytes_read, output.last().copied().unwrap_or(initial))
+}
+
+/// Decode consecutive delta encoded segments of a non decreasing sequence into one contiguous output.
+///
+/// Every segment is described by its encoded bytes and its number of integers. The first segment is decoded
+/// with an initial value of 0 and every further segment with the last value of the previous segment, which
+/// is how [`decode_delta_chained`] threads segments, so `output` holds one continuous non decreasing
+/// sequence. All segments are validated like in [`decode_delta_checked`] before any of them is decoded, so
+/// `output` is left untouched on error.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_delta_segments,encode_delta};
+/// let first = encode_delta(&[1,2,44],0);
+/// let second = encode_delta(&[64,71,534],44);
+/// let mut recovered = vec![0;6];
+/// let bytes_read = decode_delta_segments(&[(&first,3),(&second,3)],&mut recovered).unwrap();
+/// assert_eq!(bytes_read,first.len() + second.len());
+/// assert_eq!(recovered,vec![1,2,44,64,71,534]);
+/// assert!(decode_delta_segments(&[(&first,3),(&second[..4],3)],&mut recovered).is_err());
+/// ```
+/// # Return
+///
+/// Returns the number of bytes processed from all segments during decoding,
+/// [`StreamVbyteError::TruncatedStream`] if a segment is too short for its count or
+/// [`StreamVbyteError::OutputLenMismatch`] if the length of `output` differs from the sum of the segment counts
+///
+pub fn decode_delta_segments(
+    segments: &[(&[u8], usize)],
+    output: &mut [u32],
+) -> Result<usize, StreamVbyteError> {
+    let total = segments
+        .iter()
+        .try_fold(0usize, |total, &(_, count)| total.checked_add(count));
+    if total != Some(output.len()) {
+        return Err(StreamVbyteError::OutputLenMismatch(
+            output.len(),
+            total.unwrap_or(usize::MAX),
+        ));
+    }
+    // all segments are validated first so a bad segment leaves `output` untouched
+    for &(input, count) in segments {
+        validate_stream(input, count)?;
+    }
+    let mut bytes_read = 0;
+    let mut initial = 0;
+    let mut rest = output;
+    for &(input, count) in segments {
+        let (segment, tail) = rest.split_at_mut(count);
+        bytes_read += Codec::Delta.decode_checked(input, segment, initial)?;
+        initial = segment.last().copied().unwrap_or(initial);
+        rest = tail;
+    }
+    Ok(bytes_read)
+}
+
+/// Decode `count` non decreasing u32 integers from a vbyte encoded byte representation into a new buffer.
+///
+/// # Arguments
+///
+/// * `input` - The input sequence of vbyte encoding (u8s)
+/// * `count` - The number of encoded integers
+/// * `initial` - The intial value thaw was substract from the all the value in the array during encoding.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_delta_to_vec,encode_delta};
+/// let out_buf = encode_delta(&[1,2,44,64,71,534],1);
+/// let recovered = decode_delta_to_vec(&out_buf,6,1);
+/// assert_eq!(recovered,vec![1,2,44,64,71,534]);
+/// ```
 /// # Return
 ///
-/// Returns the number of bytes processed from input during decoding
+/// Returns the recovered integers
 ///
-pub fn decode(input: &[u8], output: &mut [u32]) -> usize {
-    unsafe {
-        streamvbyte_sys::streamvbyte_decode(
-            input.as_ptr(),
-            output.as_mut_ptr(),
-            output.len() as u32,
-        ) as usize
-    }
+#[cfg(feature = "alloc")]
+pub fn decode_delta_to_vec(input: &[u8], count: usize, initial: u32) -> Vec<u32> {
+    let mut output = vec![0; count];
+    decode_delta(input, &mut output, initial);
+    output
 }
 
-/// Encode a sequence of u32 integers into a vbyte encoded byte representation into an existing buffer `output`.
-///
-/// Required: output buf is at least [`max_compressedbytes`] long.
+/// Decode `count` non decreasing u32 integers from a vbyte encoded byte representation and append them to `output`.
 ///
+/// Passing the last value of `output` as `initial` continues the sequence across segments encoded with
+/// the last value of the previous segment as their initial value.
 ///
 /// # Examples
 ///
 /// ```
-/// use streamvbyte::{max_compressedbytes,encode_to_buf};
-/// let input = vec![1,2,44,5123,43,534];
-/// let max_bytes = max_compressedbytes(input.len());
-/// let mut out_buf = vec![0;max_bytes];
-/// let bytes_written = encode_to_buf(&input,&mut out_buf);
-/// assert_eq!(bytes_written.unwrap(),10);
+/// use streamvbyte::{decode_delta_append,encode_delta};
+/// let mut recovered = Vec::new();
+/// decode_delta_append(&encode_delta(&[1,2,44],0),3,&mut recovered,0);
+/// decode_delta_append(&encode_delta(&[64,71,534],44),3,&mut recovered,44);
+/// assert_eq!(recovered,vec![1,2,44,64,71,534]);
 /// ```
+/// # Panics
+///
+/// Panics if `count` exceeds `u32::MAX`
+///
 /// # Return
 ///
-/// Returns the number of bytes written to output during encoding
+/// Returns the number of bytes processed from input during decoding
 ///
-pub fn encode_to_buf(input: &[u32], output: &mut [u8]) -> Result<usize, StreamVbyteError> {
-    let output_bytes_req = max_compressedbytes(input.len());
-    if output.len() < output_bytes_req {
-        return Err(StreamVbyteError::OutbufOverflow(
-            output.len(),
-            output_bytes_req,
-        ));
-    }
-    // SAFETY: output buf is as long as max compressed size
+#[cfg(feature = "alloc")]
+pub fn decode_delta_append(
+    input: &[u8],
+    count: usize,
+    output: &mut Vec<u32>,
+    initial: u32,
+) -> usize {
+    assert!(
+        count <= u32::MAX as usize,
+        "at most u32::MAX integers can be decoded"
+    );
+    output.reserve(count);
+    let len = output.len();
+    // SAFETY: the spare capacity holds `count` integers which are all written by the decoder
     unsafe {
-        Ok(streamvbyte_sys::streamvbyte_encode(
+        let bytes_read = streamvbyte_sys::streamvbyte_delta_decode(
             input.as_ptr(),
-            input.len() as u32,
-            output.as_mut_ptr(),
-        ) as usize)
+            output.spare_capacity_mut().as_mut_ptr() as *mut u32,
+            count as u32,
+            initial,
+        ) as usize;
+        output.set_len(len + count);
+        bytes_read
     }
 }
 
-/// Encode a sequence **non decreasing** of u32 integers into a vbyte encoded byte representation.
-/// Internally a buffer of length [`max_compressedbytes`] is allocated to store the compressed result.
+/// Encode a sequence of non-zero u32 integers into a vbyte encoded byte representation.
 ///
-/// The buffer will be truncated to the correct length before returning.
-///
-/// # Arguments
-///
-/// * `input` - The input sequence of non decreasing u32 integers
-/// * `initial` - The intial value to substract from the all the value in the array to decrease the universe. **MUST** be <= input\[0\]
+/// The encoded output is identical to calling [`encode`] on the underlying u32 values.
 ///
 /// # Examples
 ///
 /// ```
-/// use streamvbyte::encode_delta;
-/// let out_bytes: Vec<u8> = encode_delta(&[1,2,44,5123,43,534],1);
+/// use std::num::NonZeroU32;
+/// use streamvbyte::encode_nonzero;
+/// let input: Vec<NonZeroU32> = [1,2,44,5123,43,534].iter().map(|&v| NonZeroU32::new(v).unwrap()).collect();
+/// let out_bytes: Vec<u8> = encode_nonzero(&input);
 /// ```
 /// # Return
 ///
 /// Returns the encoded output as a byte buffer
 ///
-pub fn encode_delta(input: &[u32], intial: u32) -> Vec<u8> {
-    let output_bytes_req = max_compressedbytes(input.len());
-    let mut buf = vec![0; output_bytes_req];
-    // SAFETY: unwrap ok as we compute required max bytes beforehand
-    let bytes_written = encode_delta_to_buf(input, &mut buf, intial).unwrap();
-    buf.truncate(bytes_written);
-    buf
+#[cfg(feature = "alloc")]
+pub fn encode_nonzero(input: &[NonZeroU32]) -> Vec<u8> {
+    // SAFETY: NonZeroU32 is repr(transparent) over u32
+    let values = unsafe { core::slice::from_raw_parts(input.as_ptr() as *const u32, input.len()) };
+    encode(values)
 }
 
-/// Encode a sequence **non decreasing** of u32 integers into a vbyte encoded byte representation into an existing buffer `output`.
+/// Decode a sequence of non-zero u32 integers from a vbyte encoded byte representation into an existing buffer `output`.
 ///
 /// # Arguments
 ///
-/// * `input` - The input sequence of non decreasing u32 integers
-/// * `output` - The output u8 slice of at least  [`max_compressedbytes`]
-/// * `initial` - The intial value to substract from the all the value in the array to decrease the universe. **MUST** be <= input\[0\]
-///
-/// Required: output buf is at least [`max_compressedbytes`] long.
-///
+/// * `input` - The input sequence of vbyte encoding (u8s)
+/// * `output` - The output buf to store the recovered non-zero integers. **MUST** be the same size as the original input sequence
 ///
 /// # Examples
 ///
 /// ```
-/// use streamvbyte::{max_compressedbytes,encode_delta_to_buf};
-/// let input = vec![1,2,44,54,433,534];
-/// let max_bytes = max_compressedbytes(input.len());
-/// let mut out_buf = vec![0;max_bytes];
-/// let bytes_written = encode_delta_to_buf(&input,&mut out_buf,0);
-/// assert_eq!(bytes_written.unwrap(),9);
+/// use std::num::NonZeroU32;
+/// use streamvbyte::{decode_nonzero,encode};
+/// let out_buf = encode(&[1,2,44,5123,43,534]);
+/// let mut recovered = vec![NonZeroU32::new(1).unwrap();6];
+/// let bytes_read = decode_nonzero(&out_buf,&mut recovered).unwrap();
+/// assert_eq!(bytes_read,out_buf.len());
+///
+/// let out_buf = encode(&[1,0,3]);
+/// let mut recovered = vec![NonZeroU32::new(1).unwrap();3];
+/// assert!(decode_nonzero(&out_buf,&mut recovered).is_err());
 /// ```
+///
 /// # Return
 ///
-/// Returns the number of bytes written to output during encoding
+/// Returns the number of bytes processed from input during decoding or
+/// [`StreamVbyteError::UnexpectedZero`] if a decoded value is zero
 ///
-pub fn encode_delta_to_buf(
-    input: &[u32],
-    output: &mut [u8],
-    initial: u32,
-) -> Result<usize, StreamVbyteError> {
-    let output_bytes_req = max_compressedbytes(input.len());
-    if output.len() < output_bytes_req {
-        return Err(StreamVbyteError::OutbufOverflow(
-            output.len(),
-            output_bytes_req,
-        ));
-    }
-    unsafe {
-        Ok(streamvbyte_sys::streamvbyte_delta_encode(
-            input.as_ptr(),
-            input.len() as u32,
-            output.as_mut_ptr(),
-            initial,
-        ) as usize)
+#[cfg(feature = "alloc")]
+pub fn decode_nonzero(input: &[u8], output: &mut [NonZeroU32]) -> Result<usize, StreamVbyteError> {
+    let mut values = vec![0; output.len()];
+    let bytes_read = decode(input, &mut values);
+    for (i, (out, value)) in output.iter_mut().zip(values).enumerate() {
+        *out = NonZeroU32::new(value).ok_or(StreamVbyteError::UnexpectedZero(i))?;
     }
+    Ok(bytes_read)
 }
 
-/// Decode a sequence of non decreasing u32 integers from a vbyte encoded byte representation into an existing buffer `output`.
+/// Decode a sequence of u32 integers into a buffer of a wrapper type `T`, e.g. a newtype around `u32`.
+///
+/// The integers are decoded into a staging buffer and converted with `T::from`, so no unsafe casts between
+/// slices of `u32` and `T` are required.
 ///
 /// # Arguments
 ///
 /// * `input` - The input sequence of vbyte encoding (u8s)
-/// * `output` - The output buf to store the recovered non decreasing u32 integers. **MUST** be the same size as the original input sequence
-/// * `initial` - The intial value thaw was substract from the all the value in the array during encoding.
+/// * `output` - The output buf to store the recovered values. **MUST** be the same size as the original input sequence
 ///
 /// # Examples
 ///
 /// ```
-/// use streamvbyte::{max_compressedbytes,encode_delta,decode_delta};
-/// let input = vec![1,2,44,5123,43,534];
-/// let out_buf = encode_delta(&input,1);
-/// let mut recovered = vec![0;6];
-/// let bytes_read = decode_delta(&out_buf,&mut recovered,1);
+/// use streamvbyte::{decode_into_wrapper,encode};
+/// #[derive(Debug,Clone,Copy,PartialEq)]
+/// struct DocId(u32);
+/// impl From<u32> for DocId {
+///     fn from(id: u32) -> DocId { DocId(id) }
+/// }
+/// let out_buf = encode(&[1,2,44]);
+/// let mut recovered = vec![DocId(0);3];
+/// let bytes_read = decode_into_wrapper(&out_buf,&mut recovered);
 /// assert_eq!(bytes_read,out_buf.len());
+/// assert_eq!(recovered,vec![DocId(1),DocId(2),DocId(44)]);
 /// ```
+///
 /// # Return
 ///
 /// Returns the number of bytes processed from input during decoding
 ///
-pub fn decode_delta(input: &[u8], output: &mut [u32], initial: u32) -> usize {
-    unsafe {
-        streamvbyte_sys::streamvbyte_delta_decode(
-            input.as_ptr(),
-            output.as_mut_ptr(),
-            output.len() as u32,
-            initial,
-        ) as usize
+#[cfg(feature = "alloc")]
+pub fn decode_into_wrapper<T: From<u32> + Copy>(input: &[u8], output: &mut [T]) -> usize {
+    let mut values = vec![0; output.len()];
+    let bytes_read = decode(input, &mut values);
+    for (out, value) in output.iter_mut().zip(values) {
+        *out = T::from(value);
     }
+    bytes_read
 }
 
 #[cfg(test)]
@@ -282,6 +1527,70 @@ mod tests {
         vec
     }
 
+    /// Inputs and their exact encoding in version 1 of the format
+    const GOLDEN_PLAIN: &[(&[u32], &[u8])] = &[
+        (&[], &[]),
+        (&[0], &[0x00, 0x00]),
+        (
+            &[1, 2, 44, 5123, 43, 534],
+            &[0x40, 0x04, 0x01, 0x02, 0x2C, 0x03, 0x14, 0x2B, 0x16, 0x02],
+        ),
+        (
+            &[
+                0xFF,
+                0x100,
+                0xFFFF,
+                0x10000,
+                0xFF_FFFF,
+                0x100_0000,
+                u32::MAX,
+            ],
+            &[
+                0x94, 0x3E, 0xFF, 0x00, 0x01, 0xFF, 0xFF, 0x00, 0x00, 0x01, 0xFF, 0xFF, 0xFF, 0x00,
+                0x00, 0x00, 0x01, 0xFF, 0xFF, 0xFF, 0xFF,
+            ],
+        ),
+    ];
+
+    /// Inputs with their initial value and their exact delta encoding in version 1 of the format
+    const GOLDEN_DELTA: &[(&[u32], u32, &[u8])] = &[
+        (&[], 0, &[]),
+        (
+            &[1, 2, 44, 64, 71, 534],
+            0,
+            &[0x00, 0x04, 0x01, 0x01, 0x2A, 0x14, 0x07, 0xCF, 0x01],
+        ),
+        (
+            &[100, 100, 356, 70000, 70000],
+            90,
+            &[0x90, 0x00, 0x0A, 0x00, 0x00, 0x01, 0x0C, 0x10, 0x01, 0x00],
+        ),
+    ];
+
+    #[test]
+    fn golden_vectors_are_stable() {
+        assert_eq!(super::FORMAT_VERSION, 1);
+        for &(input, expected) in GOLDEN_PLAIN {
+            assert_eq!(super::encode(input), expected);
+            assert_eq!(super::decode_to_vec(expected, input.len()), input);
+        }
+        for &(input, initial, expected) in GOLDEN_DELTA {
+            assert_eq!(super::encode_delta(input, initial), expected);
+            assert_eq!(
+                super::decode_delta_to_vec(expected, input.len(), initial),
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn encode_matches_little_endian_bytes() {
+        let input = [1, 2, 44, 5123, 43, 534];
+        let expected = [0x40, 0x04, 0x01, 0x02, 0x2C, 0x03, 0x14, 0x2B, 0x16, 0x02];
+        assert_eq!(super::reference::encode_reference(&input), expected);
+        assert_eq!(super::encode(&input), expected);
+    }
+
     #[test]
     fn encode_decode_roundtrip() {
         let len = 10000;
@@ -328,4 +1637,670 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn reference_matches_ffi_on_roundtrip_corpus() {
+        use super::reference::{decode_delta_reference, decode_reference};
+        let len = 10000;
+        for bits in 1..=32 {
+            let input = create_input(bits, len);
+            let output_buf = super::encode(&input);
+            let mut ffi = vec![0; len];
+            let mut pure = vec![0; len];
+            assert_eq!(
+                decode_reference(&output_buf, &mut pure),
+                super::decode(&output_buf, &mut ffi)
+            );
+            assert_eq!(pure, ffi);
+        }
+        for bits in 1..=16 {
+            let input = create_delta_input(bits, len);
+            let output_buf = super::encode_delta(&input, 0);
+            let mut ffi = vec![0; len];
+            let mut pure = vec![0; len];
+            assert_eq!(
+                decode_delta_reference(&output_buf, &mut pure, 0),
+                super::decode_delta(&output_buf, &mut ffi, 0)
+            );
+            assert_eq!(pure, ffi);
+        }
+    }
+
+    #[test]
+    fn encode_decode_nonzero_roundtrip() {
+        use std::num::NonZeroU32;
+        let len = 10000;
+        for bits in 1..=32 {
+            let input: Vec<NonZeroU32> = create_input(bits, len)
+                .into_iter()
+                .map(|v| NonZeroU32::new(v.max(1)).unwrap())
+                .collect();
+            let output_buf = super::encode_nonzero(&input);
+            let mut recovered = vec![NonZeroU32::new(1).unwrap(); len];
+            let read_bytes = super::decode_nonzero(&output_buf, &mut recovered).unwrap();
+            assert_eq!(read_bytes, output_buf.len());
+            assert_eq!(recovered, input);
+        }
+    }
+
+    #[test]
+    fn encode_delta_checked_rejects_decreasing_input() {
+        use super::{encode_delta, encode_delta_checked, StreamVbyteError};
+        let input = [3, 3, 7, 100, 100];
+        assert_eq!(
+            encode_delta_checked(&input, 3).unwrap(),
+            encode_delta(&input, 3)
+        );
+        assert_eq!(encode_delta_checked(&[], 9).unwrap(), Vec::<u8>::new());
+        for (input, initial, index) in [(&[5, 4][..], 0, 1), (&[5, 6], 6, 0), (&[1, 2, 3, 0], 1, 3)]
+        {
+            assert!(matches!(
+                encode_delta_checked(input, initial),
+                Err(StreamVbyteError::NotMonotone { index: i }) if i == index
+            ));
+        }
+    }
+
+    #[test]
+    fn encode_delta_strict_rejects_large_initial() {
+        use super::{encode_delta, encode_delta_strict, StreamVbyteError};
+        for (input, initial) in [(&[3, 3, 7][..], 3), (&[5, 4], 0), (&[], u32::MAX)] {
+            assert_eq!(
+                encode_delta_strict(input, initial).unwrap(),
+                encode_delta(input, initial)
+            );
+        }
+        assert!(matches!(
+            encode_delta_strict(&[5, 6], 6),
+            Err(StreamVbyteError::InvalidInitial {
+                initial: 6,
+                first: 5
+            })
+        ));
+    }
+
+    #[test]
+    fn decode_into_vec_reuses_allocation() {
+        let input = create_input(20, 1001);
+        let output_buf = super::encode(&input);
+        let mut recovered = vec![7; 2000];
+        let capacity = recovered.capacity();
+        assert_eq!(
+            super::decode_into_vec(&output_buf, input.len(), &mut recovered),
+            output_buf.len()
+        );
+        assert_eq!(recovered, input);
+        assert_eq!(recovered.capacity(), capacity);
+        super::decode_into_vec(&super::encode(&input[..4]), 4, &mut recovered);
+        assert_eq!(recovered, &input[..4]);
+        assert_eq!(recovered.capacity(), capacity);
+    }
+
+    #[test]
+    fn decode_into_wrapper_converts_values() {
+        let input = create_input(32, 1001);
+        let output_buf = super::encode(&input);
+        let mut recovered = vec![0u64; input.len()];
+        assert_eq!(
+            super::decode_into_wrapper(&output_buf, &mut recovered),
+            output_buf.len()
+        );
+        assert!(recovered
+            .iter()
+            .zip(&input)
+            .all(|(&r, &v)| r == u64::from(v)));
+    }
+
+    #[test]
+    fn decode_nonzero_rejects_zero() {
+        let output_buf = super::encode(&[4, 5, 0, 6]);
+        let mut recovered = vec![std::num::NonZeroU32::new(1).unwrap(); 4];
+        let err = super::decode_nonzero(&output_buf, &mut recovered).unwrap_err();
+        assert!(matches!(err, super::StreamVbyteError::UnexpectedZero(2)));
+    }
+
+    #[test]
+    fn decode_checked_rejects_truncated_input() {
+        let input = create_input(20, 1001);
+        let output_buf = super::encode(&input);
+        let mut recovered = vec![0; input.len()];
+        for len in [0, 1, 250, 251, output_buf.len() - 1] {
+            let err = super::decode_checked(&output_buf[..len], &mut recovered).unwrap_err();
+            assert!(matches!(err, super::StreamVbyteError::TruncatedStream(is, _) if is == len));
+        }
+        let read_bytes = super::decode_checked(&output_buf, &mut recovered).unwrap();
+        assert_eq!(read_bytes, output_buf.len());
+        assert_eq!(recovered, input);
+    }
+
+    #[test]
+    fn decode_delta_checked_rejects_truncated_input() {
+        let input: Vec<u32> = (0..1001u32).map(|v| v * 1013 + (v >> 3)).collect();
+        let output_buf = super::encode_delta(&input, 0);
+        let mut recovered = vec![0; input.len()];
+        for len in [0, 1, 250, 251, output_buf.len() - 1] {
+            let err =
+                super::decode_delta_checked(&output_buf[..len], &mut recovered, 0).unwrap_err();
+            assert!(matches!(err, super::StreamVbyteError::TruncatedStream(is, _) if is == len));
+        }
+        let read_bytes = super::decode_delta_checked(&output_buf, &mut recovered, 0).unwrap();
+        assert_eq!(read_bytes, output_buf.len());
+        assert_eq!(recovered, input);
+    }
+
+    /// The C functions only offset their pointers by zero for empty input and never dereference them, so
+    /// the dangling pointers of empty slices are passed to them unchanged.
+    #[test]
+    fn empty_input_contract() {
+        let empty: &[u32] = &[];
+        assert_eq!(super::max_compressedbytes(0), 0);
+        assert!(super::validate_stream(&[], 0).is_ok());
+        assert_eq!(super::section_sizes(&[], 0), (0, 0));
+
+        assert!(super::encode(empty).is_empty());
+        assert!(super::encode_delta(empty, 7).is_empty());
+        assert!(super::encode_0124(empty).is_empty());
+        assert!(super::encode_zigzag(&[]).is_empty());
+        assert_eq!(super::encode_to_buf(empty, &mut []).unwrap(), 0);
+        assert_eq!(super::encode_delta_to_buf(empty, &mut [], 7).unwrap(), 0);
+        assert_eq!(super::encode_0124_to_buf(empty, &mut []).unwrap(), 0);
+        let mut out_bytes = b"prefix".to_vec();
+        assert_eq!(super::encode_onto(empty, &mut out_bytes), 0);
+        assert_eq!(out_bytes, b"prefix");
+
+        assert_eq!(super::decode(&[], &mut []), 0);
+        assert_eq!(super::decode_delta(&[], &mut [], 7), 0);
+        assert_eq!(super::decode_0124(&[], &mut []), 0);
+        assert_eq!(super::decode_zigzag(&[], &mut []), 0);
+        assert_eq!(super::decode_uninit(&[], &mut []), 0);
+        assert_eq!(super::decode_checked(&[], &mut []).unwrap(), 0);
+        assert_eq!(super::decode_delta_checked(&[], &mut [], 7).unwrap(), 0);
+        assert!(super::decode_exact(&[], &mut []).is_ok());
+        assert!(super::decode_to_vec(&[], 0).is_empty());
+        assert!(super::decode_delta_to_vec(&[], 0, 7).is_empty());
+        assert!(super::decode_all(&[]).unwrap().is_empty());
+        let mut values = vec![1, 2];
+        assert_eq!(super::decode_append(&[], 0, &mut values), 0);
+        assert_eq!(super::decode_delta_append(&[], 0, &mut values, 7), 0);
+        assert_eq!(values, vec![1, 2]);
+
+        assert!(super::decode_framed(&super::encode_framed(empty))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn decode_from_misaligned_input() {
+        let input = create_input(20, 1001);
+        let ascending: Vec<u32> = input
+            .iter()
+            .scan(0, |sum, &v| {
+                *sum += v >> 10;
+                Some(*sum)
+            })
+            .collect();
+        let plain = super::encode(&input);
+        let delta = super::encode_delta(&ascending, 0);
+        for offset in 1..8 {
+            let mut buf = vec![0xFF; offset];
+            buf.extend_from_slice(&plain);
+            buf.extend_from_slice(&delta);
+            let mut recovered = vec![0; input.len()];
+            let bytes_read = super::decode(&buf[offset..], &mut recovered);
+            assert_eq!(bytes_read, plain.len());
+            assert_eq!(recovered, input);
+            let bytes_read = super::decode_delta(&buf[offset + plain.len()..], &mut recovered, 0);
+            assert_eq!(bytes_read, delta.len());
+            assert_eq!(recovered, ascending);
+        }
+    }
+
+    #[test]
+    fn decode_all_derives_the_count() {
+        for len in [0, 1, 3, 4, 5, 1001] {
+            let input = create_input(20, len);
+            let output_buf = super::encode(&input);
+            assert_eq!(super::decode_all(&output_buf).unwrap(), input);
+        }
+        // trailing codes of zero are valid encodings of one byte values
+        assert_eq!(super::decode_all(&[0x00, 0x05, 0x06]).unwrap(), vec![5, 6]);
+        let output_buf = super::encode(&[1, 2, 44, 5123, 43, 534]);
+        assert!(matches!(
+            super::decode_all(&output_buf[..9]),
+            Err(super::StreamVbyteError::LengthMismatch {
+                consumed: 8,
+                total: 9
+            })
+        ));
+        assert!(super::decode_all(&[0x03, 0x01]).is_err());
+    }
+
+    #[test]
+    fn decode_exact_rejects_wrong_lengths() {
+        let input = create_input(20, 1001);
+        let output_buf = super::encode(&input);
+        let mut recovered = vec![0; input.len()];
+        super::decode_exact(&output_buf, &mut recovered).unwrap();
+        assert_eq!(recovered, input);
+
+        let err = super::decode_exact(&output_buf, &mut recovered[..1000]).unwrap_err();
+        assert!(matches!(
+            err,
+            super::StreamVbyteError::LengthMismatch { consumed, total }
+                if consumed < total && total == output_buf.len()
+        ));
+        let mut too_many = vec![0; input.len() + 1];
+        let err = super::decode_exact(&output_buf, &mut too_many).unwrap_err();
+        assert!(matches!(err, super::StreamVbyteError::TruncatedStream(..)));
+        assert!(super::decode_exact(&[], &mut []).is_ok());
+    }
+
+    #[test]
+    fn encode_onto_keeps_prior_contents() {
+        let mut out_bytes = b"header".to_vec();
+        let mut expected = out_bytes.clone();
+        for len in [1000u32, 0, 3, 17] {
+            let input: Vec<u32> = (0..len).map(|v| v * 1013 + (v >> 2)).collect();
+            let segment = super::encode(&input);
+            assert_eq!(super::encode_onto(&input, &mut out_bytes), segment.len());
+            expected.extend_from_slice(&segment);
+            assert_eq!(out_bytes, expected);
+        }
+    }
+
+    #[test]
+    fn decode_append_keeps_prior_contents() {
+        let segments: Vec<Vec<u32>> = (0..20).map(|s| create_delta_input(12, s * 37)).collect();
+        let mut plain = vec![7, 8, 9];
+        let mut delta = Vec::new();
+        let mut expected = Vec::new();
+        for segment in &segments {
+            let last = delta.last().copied().unwrap_or(0);
+            let continued: Vec<u32> = segment.iter().map(|v| v + last).collect();
+            let output_buf = super::encode(segment);
+            assert_eq!(
+                super::decode_append(&output_buf, segment.len(), &mut plain),
+                output_buf.len()
+            );
+            let output_buf = super::encode_delta(&continued, last);
+            assert_eq!(
+                super::decode_delta_append(&output_buf, segment.len(), &mut delta, last),
+                output_buf.len()
+            );
+            expected.extend_from_slice(&continued);
+        }
+        let concatenated: Vec<u32> = segments.concat();
+        assert_eq!(&plain[..3], &[7, 8, 9]);
+        assert_eq!(&plain[3..], &concatenated[..]);
+        assert_eq!(delta, expected);
+    }
+
+    #[test]
+    fn error_messages_contain_context() {
+        use super::StreamVbyteError;
+        let messages = [
+            (StreamVbyteError::OutbufOverflow(7, 13), vec!["7", "13"]),
+            (StreamVbyteError::UnexpectedZero(42), vec!["42"]),
+            (StreamVbyteError::TruncatedStream(17, 23), vec!["17", "23"]),
+            (
+                StreamVbyteError::InvalidMagic(0x12, 0x56),
+                vec!["0x12", "0x56"],
+            ),
+            (StreamVbyteError::UnknownCodec(9), vec!["9"]),
+            (StreamVbyteError::OutputLenMismatch(4, 6), vec!["4", "6"]),
+            (
+                StreamVbyteError::ChecksumMismatch(0xdeadbeef, 0x1234),
+                vec!["0xdeadbeef", "0x00001234"],
+            ),
+            (
+                StreamVbyteError::LengthTooLarge(5000000000),
+                vec!["5000000000"],
+            ),
+            (StreamVbyteError::NotMonotone { index: 31 }, vec!["31"]),
+            (StreamVbyteError::DeltaOverflow { index: 77 }, vec!["77"]),
+            (
+                StreamVbyteError::LengthMismatch {
+                    consumed: 19,
+                    total: 29,
+                },
+                vec!["19", "29"],
+            ),
+            (
+                StreamVbyteError::InvalidInitial {
+                    initial: 70,
+                    first: 60,
+                },
+                vec!["70", "60"],
+            ),
+            (
+                StreamVbyteError::IndexOutOfBounds {
+                    index: 12,
+                    count: 9,
+                },
+                vec!["12", "9"],
+            ),
+            (
+                StreamVbyteError::InsufficientWidth {
+                    index: 41,
+                    width: 3,
+                },
+                vec!["41", "3"],
+            ),
+            (
+                StreamVbyteError::ValueTooLarge {
+                    index: 53,
+                    value: 70000,
+                },
+                vec!["53", "70000"],
+            ),
+            (
+                StreamVbyteError::RoundtripMismatch {
+                    index: 3,
+                    expected: 4096,
+                    actual: 8192,
+                },
+                vec!["3", "4096", "8192"],
+            ),
+        ];
+        for (err, expected) in &messages {
+            let msg = err.to_string();
+            for number in expected {
+                assert!(msg.contains(number), "{:?} missing in {:?}", number, msg);
+            }
+        }
+        let msg = StreamVbyteError::LengthTooLarge(usize::MAX).to_string();
+        assert!(msg.contains(&usize::MAX.to_string()));
+    }
+
+    #[test]
+    fn max_compressedbytes_is_the_c_worst_case() {
+        // values of four bytes reach the bound of the C library exactly, any other input stays below it
+        for len in [0, 1, 3, 4, 5, 1000, 1001] {
+            let widest = vec![u32::MAX; len];
+            assert_eq!(
+                crate::encode(&widest).len(),
+                super::max_compressedbytes(len)
+            );
+            let mut out_buf = vec![0; super::max_compressedbytes(len)];
+            for value in [0, 1 << 8, 1 << 16, 1 << 24] {
+                let written = super::encode_to_buf(&vec![value; len], &mut out_buf).unwrap();
+                assert!(written <= super::max_compressedbytes(len));
+            }
+        }
+    }
+
+    #[test]
+    fn try_max_compressedbytes_rejects_huge_lengths() {
+        use super::{max_compressedbytes, try_max_compressedbytes, StreamVbyteError};
+        for len in [0, 1, 4, 5, 1000] {
+            assert_eq!(
+                try_max_compressedbytes(len).unwrap(),
+                max_compressedbytes(len)
+            );
+        }
+        let max = u32::MAX as usize;
+        if let Ok(bytes) = try_max_compressedbytes(max) {
+            assert_eq!(bytes, max_compressedbytes(max));
+        }
+        for len in [max.saturating_add(1), usize::MAX / 4 + 1, usize::MAX] {
+            assert!(matches!(
+                try_max_compressedbytes(len),
+                Err(StreamVbyteError::LengthTooLarge(l)) if l == len
+            ));
+        }
+    }
+
+    #[test]
+    fn try_encode_matches_encode() {
+        use super::{encode, encode_delta, try_encode, try_encode_delta};
+        for len in [0, 1, 3, 4, 5, 1000] {
+            let input: Vec<u32> = (0..len as u32).map(|v| v * v * 17).collect();
+            assert_eq!(try_encode(&input).unwrap(), encode(&input));
+            assert_eq!(
+                try_encode_delta(&input, 0).unwrap(),
+                encode_delta(&input, 0)
+            );
+        }
+    }
+
+    #[test]
+    fn decode_delta_segments_threads_initial() {
+        use super::{decode_delta_segments, encode_delta, StreamVbyteError};
+        let input: Vec<u32> = (0..1001u32).map(|v| v * 7 + v % 3).collect();
+        for split in [0, 1, 4, 5, 500, 1001] {
+            let (head, tail) = input.split_at(split);
+            let first = encode_delta(head, 0);
+            let second = encode_delta(tail, head.last().copied().unwrap_or(0));
+            let empty = encode_delta(&[], 0);
+            let segments = [
+                (&first[..], head.len()),
+                (&empty[..], 0),
+                (&second[..], tail.len()),
+            ];
+            let mut recovered = vec![0; input.len()];
+            assert_eq!(
+                decode_delta_segments(&segments, &mut recovered).unwrap(),
+                first.len() + second.len()
+            );
+            assert_eq!(recovered, input);
+        }
+        let out_bytes = encode_delta(&input, 0);
+        assert_eq!(
+            decode_delta_segments(&[(&out_bytes, input.len())], &mut vec![0; input.len()]).unwrap(),
+            out_bytes.len()
+        );
+        assert!(matches!(
+            decode_delta_segments(
+                &[(&out_bytes[..out_bytes.len() - 1], input.len())],
+                &mut vec![0; input.len()]
+            ),
+            Err(StreamVbyteError::TruncatedStream(..))
+        ));
+        assert_eq!(decode_delta_segments(&[], &mut []).unwrap(), 0);
+    }
+
+    #[test]
+    fn decode_delta_segments_validates_all_segments_first() {
+        use super::{decode_delta_segments, encode_delta, StreamVbyteError};
+        let first = encode_delta(&[1, 2, 44], 0);
+        let second = encode_delta(&[64, 71, 5340], 44);
+        let mut recovered = vec![7; 6];
+        assert!(matches!(
+            decode_delta_segments(&[(&first, 3), (&second[..4], 3)], &mut recovered),
+            Err(StreamVbyteError::TruncatedStream(..))
+        ));
+        assert_eq!(recovered, vec![7; 6]);
+    }
+
+    #[test]
+    fn decode_delta_segments_rejects_output_len() {
+        use super::{decode_delta_segments, encode_delta, StreamVbyteError};
+        let out_bytes = encode_delta(&[1, 2, 3], 0);
+        assert!(matches!(
+            decode_delta_segments(&[(&out_bytes, 3)], &mut [0; 4]),
+            Err(StreamVbyteError::OutputLenMismatch(4, 3))
+        ));
+        assert!(matches!(
+            decode_delta_segments(&[(&out_bytes, 3), (&[], usize::MAX)], &mut [0; 4]),
+            Err(StreamVbyteError::OutputLenMismatch(4, usize::MAX))
+        ));
+    }
+
+    #[test]
+    fn decode_nth_column_skips_earlier_columns() {
+        use super::{decode_nth_column, encode};
+        let columns: Vec<Vec<u32>> = [0usize, 5, 1000, 3, 4]
+            .iter()
+            .enumerate()
+            .map(|(c, &len)| create_input(8 * (c as u32 % 4 + 1), len))
+            .collect();
+        let counts: Vec<usize> = columns.iter().map(|c| c.len()).collect();
+        let encoded: Vec<Vec<u8>> = columns.iter().map(|c| encode(c)).collect();
+        let input = encoded.concat();
+        for (k, column) in columns.iter().enumerate() {
+            let mut recovered = vec![0; column.len()];
+            assert_eq!(
+                decode_nth_column(&input, &counts, k, &mut recovered).unwrap(),
+                encoded[k].len()
+            );
+            assert_eq!(&recovered, column);
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn decode_nth_column_of_exactly_sized_input() {
+        use super::{decode_nth_column, encode};
+        use crate::guard::GuardedBytes;
+        let mut input = encode(&super::test_input(1001));
+        input.extend(encode(&[1; 32]));
+        let guarded = GuardedBytes::new(&input);
+        let mut recovered = vec![0; 32];
+        decode_nth_column(guarded.as_slice(), &[1001, 32], 1, &mut recovered).unwrap();
+        assert_eq!(recovered, vec![1; 32]);
+    }
+
+    #[test]
+    fn decode_nth_column_rejects_invalid_input() {
+        use super::{decode_nth_column, encode, StreamVbyteError};
+        let mut input = encode(&[1, 2, 3]);
+        input.extend(encode(&[5123, 43, 534, 7]));
+        assert!(matches!(
+            decode_nth_column(&input, &[3, 4], 2, &mut []),
+            Err(StreamVbyteError::IndexOutOfBounds { index: 2, count: 2 })
+        ));
+        assert!(matches!(
+            decode_nth_column(&input, &[3, 4], 0, &mut [0; 2]),
+            Err(StreamVbyteError::OutputLenMismatch(2, 3))
+        ));
+        // truncated within the data of column 1, the data of column 0 and the control byte of column 0
+        for len in [input.len() - 1, 5, 1, 0] {
+            assert!(matches!(
+                decode_nth_column(&input[..len], &[3, 4], 1, &mut [0; 4]),
+                Err(StreamVbyteError::TruncatedStream(..))
+            ));
+        }
+        // counts claiming more integers than encoded
+        assert!(matches!(
+            decode_nth_column(&input, &[3, 400], 1, &mut [0; 400]),
+            Err(StreamVbyteError::TruncatedStream(..))
+        ));
+        assert!(matches!(
+            decode_nth_column(&input, &[300, 4], 1, &mut [0; 4]),
+            Err(StreamVbyteError::TruncatedStream(..))
+        ));
+    }
+
+    #[test]
+    fn encode_with_max_matches_encode() {
+        use super::{encode, encode_with_max};
+        for len in [0, 1, 3, 4, 5, 1001] {
+            let input = super::test_input(len);
+            let (out_bytes, max) = encode_with_max(&input);
+            assert_eq!(out_bytes, encode(&input));
+            assert_eq!(max, input.iter().copied().max().unwrap_or(0));
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn decode_checked_of_exactly_sized_input() {
+        use crate::guard::GuardedBytes;
+        // 32 one byte values form a single SIMD group whose last 16 byte load passes the end of the data
+        for input in [vec![1; 32], super::test_input(1001)] {
+            let out_bytes = super::encode(&input);
+            let guarded = GuardedBytes::new(&out_bytes);
+            let mut recovered = vec![0; input.len()];
+            assert_eq!(
+                super::decode_checked(guarded.as_slice(), &mut recovered).unwrap(),
+                out_bytes.len()
+            );
+            assert_eq!(recovered, input);
+        }
+        let input: Vec<u32> = (0..1001).collect();
+        let out_bytes = super::encode_delta(&input, 0);
+        let guarded = GuardedBytes::new(&out_bytes);
+        let mut recovered = vec![0; input.len()];
+        assert_eq!(
+            super::decode_delta_checked(guarded.as_slice(), &mut recovered, 0).unwrap(),
+            out_bytes.len()
+        );
+        assert_eq!(recovered, input);
+        let mut aliased = vec![0; input.len()];
+        super::try_decode_delta(guarded.as_slice(), &mut aliased, 0).unwrap();
+        assert_eq!(aliased, input);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn try_decode_never_reads_past_input() {
+        use crate::guard::GuardedBytes;
+        use crate::reference::decode_reference;
+        let out_bytes = super::encode(&super::test_input(100));
+        // every prefix of the encoding ends at the guard page and is decoded with counts around the true one
+        for len in 0..=out_bytes.len() {
+            let guarded = GuardedBytes::new(&out_bytes[..len]);
+            for count in (0..=110).step_by(3) {
+                let mut output = vec![0; count];
+                if let Ok(bytes_read) = super::try_decode(guarded.as_slice(), &mut output) {
+                    let mut expected = vec![0; count];
+                    assert_eq!(
+                        decode_reference(guarded.as_slice(), &mut expected),
+                        bytes_read
+                    );
+                    assert_eq!(output, expected);
+                }
+            }
+        }
+    }
+
+    mod partial_blocks {
+        use crate::{
+            decode, decode_delta, encode, encode_delta, encoded_len, DecodeCursor, BLOCK_SIZE,
+        };
+        use proptest::prelude::*;
+
+        /// Inputs of every length residue modulo the block size, holding integers of at most `bits` bits
+        fn values() -> impl Strategy<Value = Vec<u32>> {
+            (0..64usize, 0..BLOCK_SIZE, 0..=32u32).prop_flat_map(|(blocks, residue, bits)| {
+                let mask = ((1u64 << bits) - 1) as u32;
+                prop::collection::vec(any::<u32>(), blocks * BLOCK_SIZE + residue)
+                    .prop_map(move |v| v.into_iter().map(|value| value & mask).collect())
+            })
+        }
+
+        proptest! {
+            #![proptest_config(ProptestConfig::with_cases(512))]
+
+            #[test]
+            fn plain_roundtrip(input in values()) {
+                let encoded = encode(&input);
+                prop_assert_eq!(encoded.len(), encoded_len(&input));
+
+                let mut recovered = vec![0; input.len()];
+                prop_assert_eq!(decode(&encoded, &mut recovered), encoded.len());
+                prop_assert_eq!(&recovered, &input);
+
+                let mut cursor = DecodeCursor::new(&encoded, input.len());
+                let mut block = [0; BLOCK_SIZE];
+                let mut blocks = Vec::new();
+                while let Some(n) = cursor.next_block(&mut block) {
+                    blocks.extend_from_slice(&block[..n]);
+                }
+                prop_assert_eq!(cursor.byte_offset(), encoded.len());
+                prop_assert_eq!(&blocks, &input);
+            }
+
+            #[test]
+            fn delta_roundtrip(mut input in values(), initial in any::<u32>()) {
+                input.sort_unstable();
+                let initial = input.first().map_or(initial, |&first| initial.min(first));
+                let encoded = encode_delta(&input, initial);
+
+                let mut recovered = vec![0; input.len()];
+                prop_assert_eq!(decode_delta(&encoded, &mut recovered, initial), encoded.len());
+                prop_assert_eq!(&recovered, &input);
+            }
+        }
+    }
 }
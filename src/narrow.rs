@@ -0,0 +1,240 @@
+//! Encoding of integers narrower than u32.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use crate::layout::{code_len, control_bytes_for, validate_stream};
+use crate::reference::decode_value;
+use crate::StreamVbyteError;
+#[cfg(feature = "alloc")]
+use crate::{encode, encode_from_iter};
+
+/// Unsigned integer types which can be encoded by widening them to u32, implemented for `u8`, `u16` and `u32`.
+pub trait Encodable: Copy {
+    /// Widen `self` to u32
+    fn widen(self) -> u32;
+
+    /// Narrow `value` to `Self`, returning `None` if it does not fit
+    fn narrow(value: u32) -> Option<Self>;
+}
+
+macro_rules! impl_encodable {
+    ($($t:ty),*) => {
+        $(
+            impl Encodable for $t {
+                fn widen(self) -> u32 {
+                    u32::from(self)
+                }
+
+                fn narrow(value: u32) -> Option<Self> {
+                    <$t>::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_encodable!(u8, u16, u32);
+
+/// Encode a sequence of u16 integers into the same representation as [`encode`](crate::encode) of the widened integers.
+///
+/// Every value is stored with a length code of one or two bytes, so the output is at most
+/// `input.len() * 2 + (input.len() + 3) / 4` bytes long.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_u16,encode_u16};
+/// let input = vec![1,2,44,5123,43,u16::MAX];
+/// let out_bytes = encode_u16(&input);
+/// let mut recovered = vec![0;6];
+/// let bytes_read = decode_u16(&out_bytes,&mut recovered);
+/// assert_eq!(bytes_read,out_bytes.len());
+/// assert_eq!(recovered,input);
+/// ```
+/// # Return
+///
+/// Returns the encoded output as a byte buffer
+///
+#[cfg(feature = "alloc")]
+pub fn encode_u16(input: &[u16]) -> Vec<u8> {
+    encode_from_iter(input.iter().map(|&v| u32::from(v))).0
+}
+
+/// Decode a sequence of u16 integers encoded by [`encode_u16`] into an existing buffer `output`.
+///
+/// # Arguments
+///
+/// * `input` - The input sequence of vbyte encoding (u8s)
+/// * `output` - The output buf to store the recovered u16 integers. **MUST** be the same size as the original input sequence
+///
+/// # Panics
+///
+/// Panics if `input` is too short to hold `output.len()` integers or holds a value exceeding `u16::MAX`
+///
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding
+///
+pub fn decode_u16(input: &[u8], output: &mut [u16]) -> usize {
+    match decode_narrow(input, output) {
+        Ok(bytes_read) => bytes_read,
+        Err(StreamVbyteError::ValueTooLarge { index, value }) => {
+            panic!("value {} at index {} exceeds u16::MAX", value, index)
+        }
+        Err(e) => unreachable!("{}", e),
+    }
+}
+
+/// Encode a sequence of [`Encodable`] integers into the same representation as [`encode`] of the widened integers.
+///
+/// The input is widened into a staging buffer of u32 integers which is then encoded with [`encode`].
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_any,encode,encode_any};
+/// let input: Vec<u8> = vec![1,2,44,123,43,u8::MAX];
+/// let out_bytes = encode_any(&input);
+/// assert_eq!(out_bytes,encode(&[1,2,44,123,43,255]));
+/// let mut recovered = vec![0u8;6];
+/// assert_eq!(decode_any(&out_bytes,&mut recovered).unwrap(),out_bytes.len());
+/// assert_eq!(recovered,input);
+/// ```
+/// # Return
+///
+/// Returns the encoded output as a byte buffer
+///
+#[cfg(feature = "alloc")]
+pub fn encode_any<T: Encodable>(input: &[T]) -> Vec<u8> {
+    let widened: Vec<u32> = input.iter().map(|&v| v.widen()).collect();
+    encode(&widened)
+}
+
+/// Decode a sequence of [`Encodable`] integers into an existing buffer `output`, narrowing every decoded value to `T`.
+///
+/// Unlike [`decode_u16`] this validates `input` and never panics, a value which does not fit `T` is
+/// reported instead of being truncated.
+///
+/// # Arguments
+///
+/// * `input` - The input sequence of vbyte encoding (u8s)
+/// * `output` - The output buf to store the recovered integers. **MUST** be the same size as the original input sequence
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_any,encode};
+/// let out_bytes = encode(&[1,2,44,70000]);
+/// let mut recovered = vec![0u32;4];
+/// assert_eq!(decode_any(&out_bytes,&mut recovered).unwrap(),out_bytes.len());
+/// assert_eq!(recovered,vec![1,2,44,70000]);
+/// assert!(decode_any(&out_bytes,&mut [0u16;4]).is_err());
+/// ```
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding,
+/// [`StreamVbyteError::TruncatedStream`] if `input` is too short or
+/// [`StreamVbyteError::ValueTooLarge`] if a decoded value does not fit `T`
+///
+pub fn decode_any<T: Encodable>(input: &[u8], output: &mut [T]) -> Result<usize, StreamVbyteError> {
+    validate_stream(input, output.len())?;
+    decode_narrow(input, output)
+}
+
+/// Decode `output.len()` integers from `input`, failing with [`StreamVbyteError::ValueTooLarge`] at the
+/// first value which does not fit `T`. Panics if `input` is too short.
+fn decode_narrow<T: Encodable>(input: &[u8], output: &mut [T]) -> Result<usize, StreamVbyteError> {
+    let (control, data) = input.split_at(control_bytes_for(output.len()));
+    let mut offset = 0;
+    for (i, out) in output.iter_mut().enumerate() {
+        let code = (control[i / 4] >> (2 * (i % 4))) & 0b11;
+        let value = decode_value(code, &data[offset..]);
+        *out = T::narrow(value).ok_or(StreamVbyteError::ValueTooLarge { index: i, value })?;
+        offset += code_len(code);
+    }
+    Ok(control.len() + offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_u16_roundtrip() {
+        for len in [0u16, 1, 5, 1000] {
+            let mut input: Vec<u16> = (0..len)
+                .map(|v| v.wrapping_mul(40503) >> (v % 16))
+                .collect();
+            input.push(u16::MAX);
+            let widened: Vec<u32> = input.iter().map(|&v| u32::from(v)).collect();
+            let out_bytes = encode_u16(&input);
+            assert_eq!(out_bytes, crate::encode(&widened));
+
+            let mut recovered = vec![0; input.len()];
+            assert_eq!(decode_u16(&out_bytes, &mut recovered), out_bytes.len());
+            assert_eq!(recovered, input);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds u16::MAX")]
+    fn decode_u16_rejects_wide_values() {
+        let out_bytes = crate::encode(&[1, 1 << 16]);
+        decode_u16(&out_bytes, &mut [0; 2]);
+    }
+
+    #[test]
+    fn encode_decode_any_roundtrip() {
+        let input = crate::test_input(1001u32);
+        let out_bytes = encode_any(&input);
+        assert_eq!(out_bytes, crate::encode(&input));
+        let mut recovered = vec![0u32; input.len()];
+        assert_eq!(
+            decode_any(&out_bytes, &mut recovered).unwrap(),
+            out_bytes.len()
+        );
+        assert_eq!(recovered, input);
+
+        let narrow: Vec<u16> = input.iter().map(|&v| v as u16).collect();
+        let out_bytes = encode_any(&narrow);
+        assert_eq!(out_bytes, encode_u16(&narrow));
+        let mut recovered = vec![0u16; narrow.len()];
+        assert_eq!(
+            decode_any(&out_bytes, &mut recovered).unwrap(),
+            out_bytes.len()
+        );
+        assert_eq!(recovered, narrow);
+
+        let bytes: Vec<u8> = input.iter().map(|&v| v as u8).collect();
+        let mut recovered = vec![0u8; bytes.len()];
+        decode_any(&encode_any(&bytes), &mut recovered).unwrap();
+        assert_eq!(recovered, bytes);
+        assert!(encode_any::<u8>(&[]).is_empty());
+    }
+
+    #[test]
+    fn decode_any_rejects_narrowing() {
+        let out_bytes = crate::encode(&[1, 255, 256, u32::MAX]);
+        assert!(matches!(
+            decode_any(&out_bytes, &mut [0u8; 4]),
+            Err(StreamVbyteError::ValueTooLarge {
+                index: 2,
+                value: 256
+            })
+        ));
+        assert!(matches!(
+            decode_any(&out_bytes, &mut [0u16; 4]),
+            Err(StreamVbyteError::ValueTooLarge {
+                index: 3,
+                value: u32::MAX
+            })
+        ));
+        assert!(matches!(
+            decode_any(&out_bytes[..out_bytes.len() - 1], &mut [0u32; 4]),
+            Err(StreamVbyteError::TruncatedStream(..))
+        ));
+        assert!(decode_any(&out_bytes, &mut [0u32; 4]).is_ok());
+    }
+}
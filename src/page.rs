@@ -0,0 +1,198 @@
+//! Fixed size pages holding a header and a vbyte encoded payload.
+//!
+//! A page is laid out as `[magic][codec][reserved: 2][count: u32][checksum: u32][payload]`, where
+//! all integers are little endian and the checksum is the CRC-32 of the payload. The space after
+//! the payload is zeroed.
+
+use std::io::{self, Write};
+
+use crate::checksum::crc32;
+use crate::layout::{code_len, control_bytes_for};
+use crate::reference::value_code;
+use crate::{encode, Codec, StreamVbyteError};
+
+/// The size of a page in bytes
+pub const PAGE_SIZE: usize = 4096;
+
+/// The number of bytes preceding the payload of a page
+pub const PAGE_HEADER_LEN: usize = 12;
+
+/// The first byte of every page
+const PAGE_MAGIC: u8 = 0x50;
+
+/// The number of leading values of `input` whose encoding fits into the payload of a page
+fn values_fitting(input: &[u32]) -> usize {
+    let capacity = PAGE_SIZE - PAGE_HEADER_LEN;
+    let mut data_bytes = 0;
+    for (i, &value) in input.iter().enumerate() {
+        data_bytes += code_len(value_code(value));
+        if control_bytes_for(i + 1) + data_bytes > capacity {
+            return i;
+        }
+    }
+    input.len()
+}
+
+/// Encode as many leading values of `input` as fit into `page`, prefixed by a page header.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_page,encode_to_page,PAGE_SIZE};
+/// let input: Vec<u32> = (0..10000).collect();
+/// let mut page = [0u8;PAGE_SIZE];
+/// let written = encode_to_page(&input,&mut page);
+/// assert!(written > 0 && written < input.len());
+/// assert_eq!(decode_page(&page).unwrap(),&input[..written]);
+/// ```
+/// # Return
+///
+/// Returns the number of values of `input` stored in the page
+///
+pub fn encode_to_page(input: &[u32], page: &mut [u8; PAGE_SIZE]) -> usize {
+    let count = values_fitting(input);
+    let payload = encode(&input[..count]);
+    page[0] = PAGE_MAGIC;
+    page[1] = Codec::Plain.tag();
+    page[2..4].copy_from_slice(&[0, 0]);
+    page[4..8].copy_from_slice(&(count as u32).to_le_bytes());
+    page[8..12].copy_from_slice(&crc32(&payload).to_le_bytes());
+    let end = PAGE_HEADER_LEN + payload.len();
+    page[PAGE_HEADER_LEN..end].copy_from_slice(&payload);
+    page[end..].iter_mut().for_each(|b| *b = 0);
+    count
+}
+
+/// Decode a page written by [`encode_to_page`] or [`PageWriter`].
+///
+/// The header, payload length and checksum are validated before decoding.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_page,encode_to_page,PAGE_SIZE};
+/// let mut page = [0u8;PAGE_SIZE];
+/// encode_to_page(&[1,2,44,5123,43,534],&mut page);
+/// assert_eq!(decode_page(&page).unwrap(),vec![1,2,44,5123,43,534]);
+/// page[20] ^= 0xFF;
+/// assert!(decode_page(&page).is_err());
+/// ```
+/// # Return
+///
+/// Returns the decoded integers
+///
+pub fn decode_page(page: &[u8; PAGE_SIZE]) -> Result<Vec<u32>, StreamVbyteError> {
+    if page[0] != PAGE_MAGIC {
+        return Err(StreamVbyteError::InvalidMagic(page[0], PAGE_MAGIC));
+    }
+    let codec = Codec::from_tag(page[1]).ok_or(StreamVbyteError::UnknownCodec(page[1]))?;
+    let count = u32::from_le_bytes([page[4], page[5], page[6], page[7]]) as usize;
+    let checksum = u32::from_le_bytes([page[8], page[9], page[10], page[11]]);
+    let payload = &page[PAGE_HEADER_LEN..];
+    let actual = crc32(&payload[..codec.encoded_size(payload, count)?]);
+    if actual != checksum {
+        return Err(StreamVbyteError::ChecksumMismatch(actual, checksum));
+    }
+    let mut output = vec![0; count];
+    codec.decode_checked(payload, &mut output, 0)?;
+    Ok(output)
+}
+
+/// Writes sequences of integers as consecutive pages of [`PAGE_SIZE`] bytes to the writer `W`.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_page,PageWriter,PAGE_SIZE};
+/// use std::convert::TryInto;
+/// let input: Vec<u32> = (0..10000).map(|v| v * 1000).collect();
+/// let mut writer = PageWriter::new(Vec::new());
+/// let pages = writer.write_values(&input).unwrap();
+/// let file = writer.into_inner().unwrap();
+/// assert_eq!(file.len(),pages * PAGE_SIZE);
+/// let mut recovered = Vec::new();
+/// for page in file.chunks(PAGE_SIZE) {
+///     recovered.extend(decode_page(page.try_into().unwrap()).unwrap());
+/// }
+/// assert_eq!(recovered,input);
+/// ```
+pub struct PageWriter<W: Write> {
+    dst: W,
+    page: Box<[u8; PAGE_SIZE]>,
+}
+
+impl<W: Write> PageWriter<W> {
+    /// Create a new page writer writing to `dst`
+    pub fn new(dst: W) -> PageWriter<W> {
+        PageWriter {
+            dst,
+            page: Box::new([0; PAGE_SIZE]),
+        }
+    }
+
+    /// Encode all values of `input` into as many pages as required and write them out.
+    ///
+    /// Returns the number of pages written
+    pub fn write_values(&mut self, input: &[u32]) -> io::Result<usize> {
+        let mut pages = 0;
+        let mut remaining = input;
+        while !remaining.is_empty() {
+            let written = encode_to_page(remaining, &mut self.page);
+            self.dst.write_all(&self.page[..])?;
+            remaining = &remaining[written..];
+            pages += 1;
+        }
+        Ok(pages)
+    }
+
+    /// Flush and return the underlying writer
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.dst.flush()?;
+        Ok(self.dst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryInto;
+
+    #[test]
+    fn pages_roundtrip_across_widths() {
+        for shift in [0, 8, 16, 24, 31] {
+            let input: Vec<u32> = (0..5000u32)
+                .map(|v| v.wrapping_mul(2654435761) >> shift)
+                .collect();
+            let mut writer = PageWriter::new(Vec::new());
+            let pages = writer.write_values(&input).unwrap();
+            let file = writer.into_inner().unwrap();
+            assert_eq!(file.len(), pages * PAGE_SIZE);
+            let recovered: Vec<u32> = file
+                .chunks(PAGE_SIZE)
+                .flat_map(|page| decode_page(page.try_into().unwrap()).unwrap())
+                .collect();
+            assert_eq!(recovered, input);
+        }
+    }
+
+    #[test]
+    fn pages_are_filled() {
+        // 4084 payload bytes hold 3267 one byte values with their 817 control bytes
+        let input = vec![7u32; 5000];
+        let mut page = [0u8; PAGE_SIZE];
+        assert_eq!(encode_to_page(&input, &mut page), 3267);
+    }
+
+    #[test]
+    fn corrupted_page_is_rejected() {
+        let mut page = [0u8; PAGE_SIZE];
+        encode_to_page(&[1, 2, 3, 4, 5], &mut page);
+        page[PAGE_HEADER_LEN + 3] ^= 1;
+        assert!(matches!(
+            decode_page(&page),
+            Err(StreamVbyteError::ChecksumMismatch(_, _))
+        ));
+        page[4] = 0xFF;
+        assert!(decode_page(&page).is_err());
+    }
+}
@@ -0,0 +1,163 @@
+//! Multi-threaded encoding and decoding of large inputs using rayon.
+//!
+//! The input is split into chunks which are encoded independently. Every chunk is prefixed by
+//! `[count: u32 little endian][len: u32 little endian]`, the number of integers and the number of
+//! encoded bytes of the chunk, so chunks can be located and decoded concurrently.
+
+use rayon::prelude::*;
+
+use crate::{encode, validate_stream, Codec, StreamVbyteError};
+
+/// The number of bytes preceding the payload of every chunk
+const CHUNK_HEADER_LEN: usize = 8;
+
+/// The largest number of integers per chunk, chosen so the encoded length always fits the u32 header field
+const MAX_CHUNK_LEN: usize = 1 << 29;
+
+/// Encode a sequence of u32 integers in parallel in chunks of `chunk_len` integers.
+///
+/// `chunk_len` is rounded up to a multiple of four so every chunk fills its control bytes. The output is
+/// **not** the regular encoding and has to be decoded with [`decode_parallel`].
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_parallel,encode_parallel};
+/// let input: Vec<u32> = (0..100000).collect();
+/// let out_bytes = encode_parallel(&input,10000);
+/// let mut recovered = vec![0;input.len()];
+/// let bytes_read = decode_parallel(&out_bytes,&mut recovered).unwrap();
+/// assert_eq!(bytes_read,out_bytes.len());
+/// assert_eq!(recovered,input);
+/// ```
+/// # Panics
+///
+/// Panics if `chunk_len` is zero or more than `2^29`
+///
+/// # Return
+///
+/// Returns the encoded chunks as a byte buffer
+///
+pub fn encode_parallel(input: &[u32], chunk_len: usize) -> Vec<u8> {
+    assert!(
+        chunk_len > 0 && chunk_len <= MAX_CHUNK_LEN,
+        "chunk_len must be between 1 and 2^29"
+    );
+    let chunk_len = chunk_len.next_multiple_of(4);
+    let chunks: Vec<Vec<u8>> = input.par_chunks(chunk_len).map(encode).collect();
+    let total = chunks.iter().map(|c| CHUNK_HEADER_LEN + c.len()).sum();
+    let mut out = Vec::with_capacity(total);
+    for (values, payload) in input.chunks(chunk_len).zip(&chunks) {
+        out.extend_from_slice(&(values.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        out.extend_from_slice(payload);
+    }
+    out
+}
+
+/// Decode chunks produced by [`encode_parallel`] in parallel into an existing buffer `output`.
+///
+/// All chunk headers and payload lengths are validated before decoding.
+///
+/// # Arguments
+///
+/// * `input` - The chunks produced by [`encode_parallel`]
+/// * `output` - The output buf to store the recovered u32 integers. **MUST** be the same size as the original input sequence
+///
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding, [`StreamVbyteError::TruncatedStream`]
+/// if a chunk is truncated or [`StreamVbyteError::OutputLenMismatch`] if `output` does not match the number
+/// of encoded integers
+///
+pub fn decode_parallel(input: &[u8], output: &mut [u32]) -> Result<usize, StreamVbyteError> {
+    let mut chunks = Vec::new();
+    let mut offset = 0;
+    let mut total = 0;
+    while offset < input.len() {
+        let header = input.get(offset..offset + CHUNK_HEADER_LEN).ok_or(
+            StreamVbyteError::TruncatedStream(input.len(), offset + CHUNK_HEADER_LEN),
+        )?;
+        let count = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let start = offset + CHUNK_HEADER_LEN;
+        let payload = input[start..]
+            .get(..len)
+            .ok_or(StreamVbyteError::TruncatedStream(
+                input.len(),
+                start.saturating_add(len),
+            ))?;
+        validate_stream(payload, count)?;
+        // the bytes following the payload let all but the last chunk skip the padded copy
+        chunks.push((count, &input[start..]));
+        offset = start + len;
+        total += count;
+    }
+    if total != output.len() {
+        return Err(StreamVbyteError::OutputLenMismatch(output.len(), total));
+    }
+
+    let mut segments = Vec::with_capacity(chunks.len());
+    let mut remaining = output;
+    for &(count, _) in &chunks {
+        let (segment, rest) = remaining.split_at_mut(count);
+        segments.push(segment);
+        remaining = rest;
+    }
+    segments
+        .into_par_iter()
+        .zip(chunks)
+        .for_each(|(segment, (_, payload))| {
+            // unwrap ok as all payloads were validated above
+            Codec::Plain.decode_checked(payload, segment, 0).unwrap();
+        });
+    Ok(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode;
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn parallel_of_exactly_sized_input() {
+        use crate::guard::GuardedBytes;
+        let input = vec![1; 1024];
+        for chunk_len in [32, 512] {
+            let guarded = GuardedBytes::new(&encode_parallel(&input, chunk_len));
+            let mut recovered = vec![0; input.len()];
+            decode_parallel(guarded.as_slice(), &mut recovered).unwrap();
+            assert_eq!(recovered, input);
+        }
+    }
+
+    #[test]
+    fn parallel_roundtrip_matches_serial() {
+        for len in [0u32, 1, 4, 999, 100001] {
+            let input = crate::test_input(len);
+            for chunk_len in [1, 5, 1000, 1 << 20] {
+                let out_bytes = encode_parallel(&input, chunk_len);
+                let mut recovered = vec![0; input.len()];
+                let bytes_read = decode_parallel(&out_bytes, &mut recovered).unwrap();
+                assert_eq!(bytes_read, out_bytes.len());
+                assert_eq!(recovered, input);
+
+                let mut serial = vec![0; input.len()];
+                decode(&encode(&input), &mut serial);
+                assert_eq!(recovered, serial);
+
+                if !out_bytes.is_empty() {
+                    assert!(
+                        decode_parallel(&out_bytes[..out_bytes.len() - 1], &mut recovered).is_err()
+                    );
+                }
+                let mut too_long = vec![0; input.len() + 1];
+                assert!(matches!(
+                    decode_parallel(&out_bytes, &mut too_long),
+                    Err(StreamVbyteError::OutputLenMismatch(..))
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Point updates of single integers in a vbyte encoded buffer without re-encoding it.
+
+use alloc::vec::Vec;
+
+use crate::layout::{code_len, control_bytes_for, data_len};
+use crate::reference::value_code;
+use crate::{validate_stream, StreamVbyteError};
+
+/// Replace the integer at `index` of the `count` integers encoded by [`encode`](crate::encode) in `buffer` with `new_value`.
+///
+/// If `new_value` occupies as many data bytes as the old value, only its data bytes are overwritten.
+/// Otherwise its length code is updated in the control section and the data section after it is
+/// shifted, which costs a copy of the remaining data bytes but no re-encoding. The result is the
+/// encoding [`encode`](crate::encode) produces for the updated input. Bytes after the encoding in `buffer` are kept.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode,patch_in_place};
+/// let mut buffer = encode(&[1,2,44,5123,43,534]);
+/// patch_in_place(&mut buffer,6,2,45).unwrap();
+/// assert_eq!(buffer,encode(&[1,2,45,5123,43,534]));
+/// patch_in_place(&mut buffer,6,0,1 << 20).unwrap();
+/// assert_eq!(buffer,encode(&[1 << 20,2,45,5123,43,534]));
+/// assert!(patch_in_place(&mut buffer,6,6,1).is_err());
+/// ```
+/// # Return
+///
+/// Returns [`StreamVbyteError::IndexOutOfBounds`] if `index >= count` and [`StreamVbyteError::TruncatedStream`]
+/// if `buffer` is too short to hold `count` integers. `buffer` is left unchanged in both cases.
+///
+pub fn patch_in_place(
+    buffer: &mut Vec<u8>,
+    count: usize,
+    index: usize,
+    new_value: u32,
+) -> Result<(), StreamVbyteError> {
+    if index >= count {
+        return Err(StreamVbyteError::IndexOutOfBounds { index, count });
+    }
+    validate_stream(buffer, count)?;
+    let control_bytes = control_bytes_for(count);
+    let offset = control_bytes + data_len(&buffer[..control_bytes], index);
+    let shift = 2 * (index % 4);
+    let old_len = code_len((buffer[index / 4] >> shift) & 0b11);
+    let code = value_code(new_value);
+    let bytes = &new_value.to_le_bytes()[..code_len(code)];
+    if bytes.len() == old_len {
+        buffer[offset..offset + old_len].copy_from_slice(bytes);
+    } else {
+        buffer[index / 4] = (buffer[index / 4] & !(0b11 << shift)) | (code << shift);
+        buffer.splice(offset..offset + old_len, bytes.iter().copied());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patches_match_reencoding() {
+        let mut input = crate::test_input(1001u32);
+        let mut buffer = crate::encode(&input);
+        for (i, index) in [0, 1, 3, 4, 500, 999, 1000].iter().enumerate() {
+            for value in [0, 0x1FF, 0x2_FFFF, u32::MAX, 0x3FF, 7 + i as u32] {
+                input[*index] = value;
+                patch_in_place(&mut buffer, input.len(), *index, value).unwrap();
+                assert_eq!(buffer, crate::encode(&input));
+            }
+        }
+
+        let mut trailing = crate::encode(&[1, 2, 3]);
+        trailing.extend_from_slice(b"tail");
+        patch_in_place(&mut trailing, 3, 1, 300).unwrap();
+        assert_eq!(
+            &trailing[..],
+            &[&crate::encode(&[1, 300, 3])[..], b"tail"].concat()[..]
+        );
+
+        assert!(matches!(
+            patch_in_place(&mut buffer, 1001, 1001, 1),
+            Err(StreamVbyteError::IndexOutOfBounds {
+                index: 1001,
+                count: 1001
+            })
+        ));
+        let mut truncated = buffer[..buffer.len() - 1].to_vec();
+        assert!(patch_in_place(&mut truncated, 1001, 0, 1).is_err());
+    }
+}
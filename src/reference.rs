@@ -0,0 +1,196 @@
+//! A pure Rust implementation of the vbyte format without `unsafe` code or FFI.
+//!
+//! The functions in this module produce and consume exactly the same bytes as their counterparts
+//! in the crate root. They are considerably slower than the C implementation and are intended to
+//! verify it.
+#![forbid(unsafe_code)]
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use crate::layout::{code_len, control_bytes_for};
+
+/// The 2-bit length code of `value` in the data section
+pub(crate) fn value_code(value: u32) -> u8 {
+    match value {
+        0..=0xFF => 0,
+        0x100..=0xFFFF => 1,
+        0x10000..=0xFF_FFFF => 2,
+        _ => 3,
+    }
+}
+
+/// Append the data section bytes of `value` to `data` and return its length code
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_value(value: u32, data: &mut Vec<u8>) -> u8 {
+    let code = value_code(value);
+    data.extend_from_slice(&value.to_le_bytes()[..code_len(code)]);
+    code
+}
+
+/// Read a value with length code `code` from the start of `data`
+pub(crate) fn decode_value(code: u8, data: &[u8]) -> u32 {
+    let mut bytes = [0; 4];
+    let len = code_len(code);
+    bytes[..len].copy_from_slice(&data[..len]);
+    u32::from_le_bytes(bytes)
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_values<I: Iterator<Item = u32>>(values: I, count: usize) -> Vec<u8> {
+    let mut out = vec![0; control_bytes_for(count)];
+    for (i, value) in values.enumerate() {
+        let code = encode_value(value, &mut out);
+        out[i / 4] |= code << (2 * (i % 4));
+    }
+    out
+}
+
+fn decode_values<F: FnMut(usize, u32)>(input: &[u8], count: usize, mut sink: F) -> usize {
+    let (control, data) = input.split_at(control_bytes_for(count));
+    let mut offset = 0;
+    for i in 0..count {
+        let code = (control[i / 4] >> (2 * (i % 4))) & 0b11;
+        sink(i, decode_value(code, &data[offset..]));
+        offset += code_len(code);
+    }
+    control.len() + offset
+}
+
+/// Encode a sequence of u32 integers into the same representation as [`encode`](crate::encode).
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::encode;
+/// use streamvbyte::reference::encode_reference;
+/// let input = vec![1,2,44,5123,43,534];
+/// assert_eq!(encode_reference(&input),encode(&input));
+/// ```
+/// # Return
+///
+/// Returns the encoded output as a byte buffer
+///
+#[cfg(feature = "alloc")]
+pub fn encode_reference(input: &[u32]) -> Vec<u8> {
+    encode_values(input.iter().copied(), input.len())
+}
+
+/// Decode a sequence of u32 integers encoded by [`encode`](crate::encode) into an existing buffer `output`.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::encode;
+/// use streamvbyte::reference::decode_reference;
+/// let out_buf = encode(&[1,2,44,5123,43,534]);
+/// let mut recovered = vec![0;6];
+/// let bytes_read = decode_reference(&out_buf,&mut recovered);
+/// assert_eq!(bytes_read,out_buf.len());
+/// assert_eq!(recovered,vec![1,2,44,5123,43,534]);
+/// ```
+/// # Panics
+///
+/// Panics if `input` is too short to hold `output.len()` integers
+///
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding
+///
+pub fn decode_reference(input: &[u8], output: &mut [u32]) -> usize {
+    decode_values(input, output.len(), |i, value| output[i] = value)
+}
+
+/// Encode a sequence of u32 integers into the same representation as [`encode_delta`](crate::encode_delta).
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::encode_delta;
+/// use streamvbyte::reference::encode_delta_reference;
+/// let input = vec![1,2,44,64,71,534];
+/// assert_eq!(encode_delta_reference(&input,1),encode_delta(&input,1));
+/// ```
+/// # Return
+///
+/// Returns the encoded output as a byte buffer
+///
+#[cfg(feature = "alloc")]
+pub fn encode_delta_reference(input: &[u32], initial: u32) -> Vec<u8> {
+    let mut prev = initial;
+    let gaps = input.iter().map(|&value| {
+        let gap = value.wrapping_sub(prev);
+        prev = value;
+        gap
+    });
+    encode_values(gaps, input.len())
+}
+
+/// Decode a sequence of u32 integers encoded by [`encode_delta`](crate::encode_delta) into an existing buffer `output`.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::encode_delta;
+/// use streamvbyte::reference::decode_delta_reference;
+/// let out_buf = encode_delta(&[1,2,44,64,71,534],1);
+/// let mut recovered = vec![0;6];
+/// let bytes_read = decode_delta_reference(&out_buf,&mut recovered,1);
+/// assert_eq!(bytes_read,out_buf.len());
+/// assert_eq!(recovered,vec![1,2,44,64,71,534]);
+/// ```
+/// # Panics
+///
+/// Panics if `input` is too short to hold `output.len()` integers
+///
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding
+///
+pub fn decode_delta_reference(input: &[u8], output: &mut [u32], initial: u32) -> usize {
+    let mut prev = initial;
+    decode_values(input, output.len(), |i, gap| {
+        prev = prev.wrapping_add(gap);
+        output[i] = prev;
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Values of random magnitude so all four length codes are exercised
+    fn values() -> impl Strategy<Value = Vec<u32>> {
+        prop::collection::vec((any::<u32>(), 0..32u32), 0..200)
+            .prop_map(|v| v.into_iter().map(|(value, shift)| value >> shift).collect())
+    }
+
+    proptest! {
+        #[test]
+        fn plain_matches_ffi(input in values()) {
+            let encoded = crate::encode(&input);
+            prop_assert_eq!(&encode_reference(&input), &encoded);
+
+            let mut ffi = vec![0; input.len()];
+            let mut pure = vec![0; input.len()];
+            prop_assert_eq!(crate::decode(&encoded, &mut ffi), encoded.len());
+            prop_assert_eq!(decode_reference(&encoded, &mut pure), encoded.len());
+            prop_assert_eq!(&ffi, &input);
+            prop_assert_eq!(&pure, &input);
+        }
+
+        #[test]
+        fn delta_matches_ffi(input in values(), initial in any::<u32>()) {
+            let encoded = crate::encode_delta(&input, initial);
+            prop_assert_eq!(&encode_delta_reference(&input, initial), &encoded);
+
+            let mut ffi = vec![0; input.len()];
+            let mut pure = vec![0; input.len()];
+            prop_assert_eq!(crate::decode_delta(&encoded, &mut ffi, initial), encoded.len());
+            prop_assert_eq!(decode_delta_reference(&encoded, &mut pure, initial), encoded.len());
+            prop_assert_eq!(&ffi, &input);
+            prop_assert_eq!(&pure, &input);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+//! Validation that the codec restores a given input, e.g. on a sample of data before ingesting it.
+
+use alloc::{vec, vec::Vec};
+
+use crate::{decode, decode_delta, encode, encode_delta, StreamVbyteError};
+
+/// Returns the first position where `decoded` differs from `input`
+fn compare(input: &[u32], decoded: &[u32]) -> Result<(), StreamVbyteError> {
+    match input.iter().zip(decoded).position(|(a, b)| a != b) {
+        Some(index) => Err(StreamVbyteError::RoundtripMismatch {
+            index,
+            expected: input[index],
+            actual: decoded[index],
+        }),
+        None => Ok(()),
+    }
+}
+
+fn decode_fresh(out_bytes: &[u8], count: usize, initial: Option<u32>) -> Vec<u32> {
+    let mut recovered = vec![0; count];
+    match initial {
+        Some(initial) => decode_delta(out_bytes, &mut recovered, initial),
+        None => decode(out_bytes, &mut recovered),
+    };
+    recovered
+}
+
+/// Encode `input` with [`encode`], decode it into a new buffer and compare the result with `input`.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::roundtrip;
+/// assert!(roundtrip(&[1,2,44,5123,43,534]).is_ok());
+/// ```
+/// # Panics
+///
+/// Panics if `input` holds more than `u32::MAX` integers
+///
+/// # Return
+///
+/// Returns [`StreamVbyteError::RoundtripMismatch`] with the first differing position and values
+///
+pub fn roundtrip(input: &[u32]) -> Result<(), StreamVbyteError> {
+    compare(input, &decode_fresh(&encode(input), input.len(), None))
+}
+
+/// Encode `input` with [`encode_delta`], decode it into a new buffer and compare the result with `input`.
+///
+/// The differences and prefix sums of the delta encoding wrap around, so every input is expected to be
+/// restored, even one that is not non decreasing.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::roundtrip_delta;
+/// assert!(roundtrip_delta(&[1,2,44,64,71,534],1).is_ok());
+/// ```
+/// # Panics
+///
+/// Panics if `input` holds more than `u32::MAX` integers
+///
+/// # Return
+///
+/// Returns [`StreamVbyteError::RoundtripMismatch`] with the first differing position and values
+///
+pub fn roundtrip_delta(input: &[u32], initial: u32) -> Result<(), StreamVbyteError> {
+    compare(
+        input,
+        &decode_fresh(&encode_delta(input, initial), input.len(), Some(initial)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_reports_first_mismatch() {
+        let input = crate::test_input(1001u32);
+        roundtrip(&input).unwrap();
+        roundtrip_delta(&input, 7).unwrap();
+        roundtrip(&[]).unwrap();
+
+        let mut decoded = input.clone();
+        decoded[17] ^= 1;
+        decoded[500] ^= 1;
+        assert!(matches!(
+            compare(&input, &decoded),
+            Err(StreamVbyteError::RoundtripMismatch { index: 17, expected, actual })
+                if expected == input[17] && actual == decoded[17]
+        ));
+    }
+}
@@ -0,0 +1,135 @@
+//! Encoders and decoders owning a reusable buffer to avoid an allocation per call.
+
+use alloc::vec::Vec;
+
+use crate::{decode, decode_delta, encode_delta_to_buf, encode_to_buf, max_compressedbytes};
+
+/// Encodes sequences of u32 integers into an owned buffer which is reused across calls.
+///
+/// The buffer only grows, so encoding many small sequences allocates at most a handful of times.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode,Encoder};
+/// let mut encoder = Encoder::new();
+/// for input in &[vec![1,2,44,5123,43,534],vec![7;100]] {
+///     let out_bytes = encoder.encode(input);
+///     assert_eq!(out_bytes,&encode(input)[..]);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Encoder {
+    buf: Vec<u8>,
+}
+
+impl Encoder {
+    /// Create a new encoder with an empty buffer
+    pub fn new() -> Encoder {
+        Encoder::default()
+    }
+
+    /// Grow the buffer to hold the encoding of `count` integers
+    fn reserve(&mut self, count: usize) -> &mut [u8] {
+        let bytes = max_compressedbytes(count);
+        if self.buf.len() < bytes {
+            self.buf.resize(bytes, 0);
+        }
+        &mut self.buf
+    }
+
+    /// Encode `input` like [`encode`](crate::encode) into the owned buffer.
+    ///
+    /// The returned bytes borrow the encoder and are overwritten by the next call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input` holds more than `u32::MAX` integers
+    pub fn encode(&mut self, input: &[u32]) -> &[u8] {
+        let bytes_written = encode_to_buf(input, self.reserve(input.len()))
+            .expect("at most u32::MAX integers can be encoded");
+        &self.buf[..bytes_written]
+    }
+
+    /// Encode the non decreasing `input` like [`encode_delta`](crate::encode_delta) into the owned buffer.
+    ///
+    /// The returned bytes borrow the encoder and are overwritten by the next call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input` holds more than `u32::MAX` integers
+    pub fn encode_delta(&mut self, input: &[u32], initial: u32) -> &[u8] {
+        let bytes_written = encode_delta_to_buf(input, self.reserve(input.len()), initial)
+            .expect("at most u32::MAX integers can be encoded");
+        &self.buf[..bytes_written]
+    }
+}
+
+/// Decodes sequences of u32 integers into an owned buffer which is reused across calls.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode,Decoder};
+/// let mut decoder = Decoder::new();
+/// let out_bytes = encode(&[1,2,44,5123,43,534]);
+/// assert_eq!(decoder.decode(&out_bytes,6),&[1,2,44,5123,43,534]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Decoder {
+    buf: Vec<u32>,
+}
+
+impl Decoder {
+    /// Create a new decoder with an empty buffer
+    pub fn new() -> Decoder {
+        Decoder::default()
+    }
+
+    /// Grow the buffer to hold `count` integers
+    fn reserve(&mut self, count: usize) -> &mut [u32] {
+        if self.buf.len() < count {
+            self.buf.resize(count, 0);
+        }
+        &mut self.buf[..count]
+    }
+
+    /// Decode `count` integers encoded by [`encode`](crate::encode) into the owned buffer.
+    ///
+    /// The returned integers borrow the decoder and are overwritten by the next call.
+    pub fn decode(&mut self, input: &[u8], count: usize) -> &[u32] {
+        decode(input, self.reserve(count));
+        &self.buf[..count]
+    }
+
+    /// Decode `count` integers encoded by [`encode_delta`](crate::encode_delta) into the owned buffer.
+    ///
+    /// The returned integers borrow the decoder and are overwritten by the next call.
+    pub fn decode_delta(&mut self, input: &[u8], count: usize, initial: u32) -> &[u32] {
+        decode_delta(input, self.reserve(count), initial);
+        &self.buf[..count]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scratch_buffers_are_reused() {
+        let mut encoder = Encoder::new();
+        let mut decoder = Decoder::new();
+        for len in [1000u32, 0, 1, 17, 999, 4] {
+            let input: Vec<u32> = (0..len).map(|v| v * 1013 + (v >> 2)).collect();
+            let out_bytes = encoder.encode(&input).to_vec();
+            assert_eq!(out_bytes, crate::encode(&input));
+            assert_eq!(decoder.decode(&out_bytes, input.len()), &input[..]);
+
+            let out_bytes = encoder.encode_delta(&input, 0).to_vec();
+            assert_eq!(out_bytes, crate::encode_delta(&input, 0));
+            assert_eq!(decoder.decode_delta(&out_bytes, input.len(), 0), &input[..]);
+        }
+        assert_eq!(encoder.buf.len(), max_compressedbytes(1000));
+        assert_eq!(decoder.buf.len(), 1000);
+    }
+}
@@ -0,0 +1,128 @@
+//! A read-only view over many framed blocks as one logical sequence of integers.
+
+use crate::framed::{parse_frame, FRAME_HEADER_LEN};
+use crate::{Codec, StreamVbyteError};
+
+/// A sequence of integers stored in independently framed blocks written by [`write_frame`](crate::write_frame).
+///
+/// Random access resolves the block holding the requested position and only decodes that block.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{write_frame,Codec,MultiBlockSequence};
+/// let mut first = Vec::new();
+/// write_frame(&mut first,&[1,2,3],Codec::Plain).unwrap();
+/// let mut second = Vec::new();
+/// write_frame(&mut second,&[10,20,30,40],Codec::Delta).unwrap();
+/// let sequence = MultiBlockSequence::new(vec![first,second]).unwrap();
+/// assert_eq!(sequence.len(),7);
+/// assert_eq!(sequence.get(4),Some(20));
+/// assert_eq!(sequence.get(7),None);
+/// assert_eq!(sequence.iter().collect::<Vec<u32>>(),vec![1,2,3,10,20,30,40]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultiBlockSequence {
+    blocks: Vec<Vec<u8>>,
+    /// `starts[i]` is the number of integers stored before block `i`
+    starts: Vec<usize>,
+    len: usize,
+}
+
+impl MultiBlockSequence {
+    /// Create a sequence from framed `blocks`, validating the header and payload length of every block
+    pub fn new(blocks: Vec<Vec<u8>>) -> Result<MultiBlockSequence, StreamVbyteError> {
+        let mut starts = Vec::with_capacity(blocks.len());
+        let mut len = 0;
+        for block in &blocks {
+            let (_, count, _) = parse_frame(block)?;
+            starts.push(len);
+            len += count;
+        }
+        Ok(MultiBlockSequence {
+            blocks,
+            starts,
+            len,
+        })
+    }
+
+    /// The total number of integers in all blocks
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the sequence holds no integers
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of blocks
+    pub fn num_blocks(&self) -> usize {
+        self.blocks.len()
+    }
+
+    fn decode_block(&self, block: usize) -> (Codec, Vec<u32>) {
+        // unwrap ok as all blocks were validated on construction
+        let (codec, count, _) = parse_frame(&self.blocks[block]).unwrap();
+        let mut values = vec![0; count];
+        codec
+            .decode_checked(&self.blocks[block][FRAME_HEADER_LEN..], &mut values, 0)
+            .unwrap();
+        (codec, values)
+    }
+
+    /// Returns the integer at position `index` or `None` if `index >= self.len()`
+    pub fn get(&self, index: usize) -> Option<u32> {
+        if index >= self.len {
+            return None;
+        }
+        // the last block starting at or before index, skipping empty blocks
+        let block = self.starts.partition_point(|&start| start <= index) - 1;
+        let (_, values) = self.decode_block(block);
+        values.get(index - self.starts[block]).copied()
+    }
+
+    /// Iterate over all integers, decoding one block at a time
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.blocks.len()).flat_map(move |block| self.decode_block(block).1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::write_frame;
+
+    #[test]
+    fn random_access_across_blocks() {
+        let mut blocks = Vec::new();
+        let mut expected = Vec::new();
+        for b in 0..20u32 {
+            let values: Vec<u32> = (0..(b * 37) % 50).map(|v| b * 1000 + v).collect();
+            let mut block = Vec::new();
+            let codec = if b % 2 == 0 {
+                Codec::Plain
+            } else {
+                Codec::Delta
+            };
+            write_frame(&mut block, &values, codec).unwrap();
+            blocks.push(block);
+            expected.extend(values);
+        }
+        let sequence = MultiBlockSequence::new(blocks).unwrap();
+        assert_eq!(sequence.len(), expected.len());
+        for (i, &value) in expected.iter().enumerate() {
+            assert_eq!(sequence.get(i), Some(value));
+        }
+        assert_eq!(sequence.get(expected.len()), None);
+        assert_eq!(sequence.iter().collect::<Vec<u32>>(), expected);
+    }
+
+    #[test]
+    fn invalid_blocks_are_rejected() {
+        let mut block = Vec::new();
+        write_frame(&mut block, &[1, 2, 3, 4, 5], Codec::Plain).unwrap();
+        block.pop();
+        assert!(MultiBlockSequence::new(vec![block]).is_err());
+    }
+}
@@ -0,0 +1,98 @@
+//! A decoder over an immutable, reference counted encoded buffer which can be shared between threads.
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::layout::{code_len, control_bytes_for, data_len};
+use crate::reference::decode_value;
+use crate::{validate_stream, StreamVbyteError};
+
+/// Decodes ranges of `count` integers encoded by [`encode`](crate::encode) and held in an `Arc<[u8]>`.
+///
+/// The decoder never mutates its state, so a single instance, or cheap clones of it, can serve
+/// concurrent reads from many threads.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use streamvbyte::{encode,SharedDecoder};
+/// let input: Vec<u32> = (0..1000).map(|v| v * 7).collect();
+/// let decoder = SharedDecoder::new(Arc::from(encode(&input)),input.len()).unwrap();
+/// let handle = {
+///     let decoder = decoder.clone();
+///     std::thread::spawn(move || decoder.decode_range(10,13))
+/// };
+/// assert_eq!(handle.join().unwrap(),vec![70,77,84]);
+/// assert_eq!(decoder.decode_range(998,1000),vec![6986,6993]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedDecoder {
+    bytes: Arc<[u8]>,
+    count: usize,
+}
+
+impl SharedDecoder {
+    /// Wrap the encoding of `count` integers, validating that `bytes` holds all of them
+    pub fn new(bytes: Arc<[u8]>, count: usize) -> Result<SharedDecoder, StreamVbyteError> {
+        validate_stream(&bytes, count)?;
+        Ok(SharedDecoder { bytes, count })
+    }
+
+    /// The number of encoded integers
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if no integers are stored
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Decode the integers at the positions `start..end`.
+    ///
+    /// The data offset of `start` is found from the control bytes, so only the integers of the range are decoded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is decreasing or ends after [`len`](SharedDecoder::len)
+    pub fn decode_range(&self, start: usize, end: usize) -> Vec<u32> {
+        assert!(
+            start <= end && end <= self.count,
+            "range {}..{} out of bounds for length {}",
+            start,
+            end,
+            self.count
+        );
+        let control = &self.bytes[..control_bytes_for(self.count)];
+        let data = &self.bytes[control.len()..];
+        let mut offset = data_len(control, start);
+        let mut output = Vec::with_capacity(end - start);
+        for i in start..end {
+            let code = (control[i / 4] >> (2 * (i % 4))) & 0b11;
+            output.push(decode_value(code, &data[offset..]));
+            offset += code_len(code);
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_decoder_ranges_match_input() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<SharedDecoder>();
+
+        let input = crate::test_input(1001u32);
+        let decoder = SharedDecoder::new(Arc::from(crate::encode(&input)), input.len()).unwrap();
+        assert_eq!(decoder.len(), input.len());
+        for (start, end) in [(0, 0), (0, 1001), (1, 4), (4, 8), (97, 405), (1000, 1001)] {
+            assert_eq!(decoder.decode_range(start, end), &input[start..end]);
+        }
+        let truncated = Arc::from(&crate::encode(&input)[..500]);
+        assert!(SharedDecoder::new(truncated, input.len()).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+//! Encoding with the control and data sections stored in separate buffers, and merging of encoded buffers.
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+#[cfg(feature = "alloc")]
+use crate::encode;
+use crate::layout::{code_len, control_bytes_for};
+use crate::reference::decode_value;
+#[cfg(feature = "alloc")]
+use crate::section_sizes;
+
+/// Encode a sequence of u32 integers like [`encode`] and return the control and data sections separately.
+///
+/// Concatenating both sections yields the output of [`encode`]. Keeping them apart allows e.g. a second
+/// compression pass over the control section only.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_split,encode,encode_split};
+/// let input = vec![1,2,44,5123,43,534];
+/// let (control,data) = encode_split(&input);
+/// assert_eq!([control.clone(),data.clone()].concat(),encode(&input));
+/// let mut recovered = vec![0;6];
+/// assert_eq!(decode_split(&control,&data,&mut recovered),data.len());
+/// assert_eq!(recovered,input);
+/// ```
+/// # Panics
+///
+/// Panics if `input` holds more than `u32::MAX` integers
+///
+/// # Return
+///
+/// Returns the control section of `(input.len() + 3) / 4` bytes and the data section
+///
+#[cfg(feature = "alloc")]
+pub fn encode_split(input: &[u32]) -> (Vec<u8>, Vec<u8>) {
+    let mut control = encode(input);
+    let data = control.split_off(control_bytes_for(input.len()));
+    (control, data)
+}
+
+/// Decode a sequence of u32 integers from separately stored control and data sections, see [`encode_split`].
+///
+/// The sections are read in place without joining them, which is not possible with the C decoder, so
+/// this is slower than [`decode`](crate::decode) on a contiguous buffer.
+///
+/// # Arguments
+///
+/// * `control` - The control section of at least `(output.len() + 3) / 4` bytes
+/// * `data` - The data section
+/// * `output` - The output buf to store the recovered u32 integers. **MUST** be the same size as the original input sequence
+///
+/// # Panics
+///
+/// Panics if `control` or `data` are too short to hold `output.len()` integers
+///
+/// # Return
+///
+/// Returns the number of bytes processed from `data` during decoding
+///
+pub fn decode_split(control: &[u8], data: &[u8], output: &mut [u32]) -> usize {
+    let control = &control[..control_bytes_for(output.len())];
+    let mut offset = 0;
+    for (i, out) in output.iter_mut().enumerate() {
+        let code = (control[i / 4] >> (2 * (i % 4))) & 0b11;
+        *out = decode_value(code, &data[offset..]);
+        offset += code_len(code);
+    }
+    offset
+}
+
+/// Concatenate the encodings of `a_count` integers in `a` and `b_count` integers in `b` without decoding them.
+///
+/// The result is the encoding produced by [`encode`] of both sequences joined. The control sections are
+/// merged code by code, as the codes of `b` continue the final partial block of `a`, and the data sections
+/// are copied unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{concat,encode};
+/// let merged = concat(&encode(&[1,2,44]),3,&encode(&[5123,43,534]),3);
+/// assert_eq!(merged,encode(&[1,2,44,5123,43,534]));
+/// ```
+/// # Panics
+///
+/// Panics if `a` or `b` are too short to hold `a_count` and `b_count` integers
+///
+/// # Return
+///
+/// Returns the encoding of the `a_count + b_count` integers as a byte buffer
+///
+#[cfg(feature = "alloc")]
+pub fn concat(a: &[u8], a_count: usize, b: &[u8], b_count: usize) -> Vec<u8> {
+    let (a_control, a_data) = section_sizes(a, a_count);
+    let (b_control, b_data) = section_sizes(b, b_count);
+    let control_bytes = control_bytes_for(a_count + b_count);
+    let mut out = vec![0; control_bytes];
+    out.reserve(a_data + b_data);
+    out[..a_control].copy_from_slice(&a[..a_control]);
+    if a_count.is_multiple_of(4) {
+        out[a_control..].copy_from_slice(&b[..b_control]);
+    } else {
+        // the unused codes of the final control byte of `a` are overwritten by the codes of `b`
+        out[a_control - 1] &= (1 << (2 * (a_count % 4))) - 1;
+        for j in 0..b_count {
+            let code = (b[j / 4] >> (2 * (j % 4))) & 0b11;
+            let i = a_count + j;
+            out[i / 4] |= code << (2 * (i % 4));
+        }
+    }
+    out.extend_from_slice(&a[a_control..a_control + a_data]);
+    out.extend_from_slice(&b[b_control..b_control + b_data]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_sections_roundtrip() {
+        for len in [0u32, 1, 5, 1001] {
+            let input = crate::test_input(len);
+            let (control, data) = encode_split(&input);
+            assert_eq!(control.len(), control_bytes_for(input.len()));
+            let mut recovered = vec![0; input.len()];
+            assert_eq!(decode_split(&control, &data, &mut recovered), data.len());
+            assert_eq!(recovered, input);
+        }
+    }
+
+    #[test]
+    fn concat_matches_joined_encoding() {
+        let input = crate::test_input(103u32);
+        for split in [0, 1, 2, 3, 4, 5, 50, 102, 103] {
+            let (a, b) = input.split_at(split);
+            let merged = concat(&encode(a), a.len(), &encode(b), b.len());
+            assert_eq!(merged, encode(&input));
+        }
+    }
+}
@@ -0,0 +1,254 @@
+//! Incremental encoding to writers and decoding from readers without holding the whole sequence in memory.
+//!
+//! The regular encoding stores all control bytes ahead of the data section, which requires the
+//! whole input up front. Streams are instead written as consecutive blocks of four integers, each
+//! block being the regular encoding of its integers `[control][data]`. The final block holds the
+//! remaining 1-3 integers, with the unused codes of its control byte set to zero. The number of
+//! integers is not part of the stream and has to be tracked by the caller.
+
+use std::io::{self, Read, Write};
+
+use crate::layout::{code_len, data_len};
+use crate::reference::decode_value;
+use crate::{encode_to_buf, max_compressedbytes};
+
+/// The number of integers per block of a stream
+pub(crate) const BLOCK_LEN: usize = 4;
+
+/// Encodes integers pushed one at a time into blocks of four and writes them to the writer `W`.
+///
+/// The output is **not** the regular encoding produced by [`encode`](crate::encode), but a sequence
+/// of blocks each holding the regular encoding of four integers. The number of pushed integers is
+/// required to decode the stream with a [`StreamVbyteReader`] and has to be stored by the caller, e.g. using
+/// [`count`](StreamVbyteWriter::count).
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::StreamVbyteWriter;
+/// let mut writer = StreamVbyteWriter::new(Vec::new());
+/// for value in &[1,2,44,5123,43,534] {
+///     writer.push(*value).unwrap();
+/// }
+/// assert_eq!(writer.count(),6);
+/// let out_bytes = writer.finish().unwrap();
+/// assert_eq!(out_bytes.len(),10);
+/// ```
+#[derive(Debug)]
+pub struct StreamVbyteWriter<W: Write> {
+    dst: W,
+    block: [u32; BLOCK_LEN],
+    block_len: usize,
+    count: u64,
+}
+
+impl<W: Write> StreamVbyteWriter<W> {
+    /// Create a new stream writer writing to `dst`
+    pub fn new(dst: W) -> StreamVbyteWriter<W> {
+        StreamVbyteWriter {
+            dst,
+            block: [0; BLOCK_LEN],
+            block_len: 0,
+            count: 0,
+        }
+    }
+
+    /// Append `value` to the stream, writing out the current block once it holds four integers
+    pub fn push(&mut self, value: u32) -> io::Result<()> {
+        self.block[self.block_len] = value;
+        self.block_len += 1;
+        self.count += 1;
+        if self.block_len == BLOCK_LEN {
+            self.write_block()?;
+        }
+        Ok(())
+    }
+
+    /// The number of integers pushed so far
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Write out the trailing partial block and flush the underlying writer.
+    ///
+    /// Integers of a partial block are lost if the writer is dropped without calling `finish`.
+    ///
+    /// Returns the underlying writer
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.block_len > 0 {
+            self.write_block()?;
+        }
+        self.dst.flush()?;
+        Ok(self.dst)
+    }
+
+    fn write_block(&mut self) -> io::Result<()> {
+        let mut buf = [0; max_compressedbytes(BLOCK_LEN)];
+        // unwrap ok as buf holds max_compressedbytes bytes of a full block
+        let bytes_written = encode_to_buf(&self.block[..self.block_len], &mut buf).unwrap();
+        self.block_len = 0;
+        self.dst.write_all(&buf[..bytes_written])
+    }
+}
+
+/// Decodes a stream written by a [`StreamVbyteWriter`] from the reader `R` one block at a time.
+///
+/// Only the bytes of the block being decoded are read, so the reader does not need to be buffered.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{StreamVbyteReader,StreamVbyteWriter};
+/// let mut writer = StreamVbyteWriter::new(Vec::new());
+/// for value in &[1,2,44,5123,43,534] {
+///     writer.push(*value).unwrap();
+/// }
+/// let out_bytes = writer.finish().unwrap();
+/// let mut reader = StreamVbyteReader::new(&out_bytes[..],6);
+/// let mut recovered = Vec::new();
+/// while let Some(value) = reader.next().unwrap() {
+///     recovered.push(value);
+/// }
+/// assert_eq!(recovered,vec![1,2,44,5123,43,534]);
+/// ```
+#[derive(Debug)]
+pub struct StreamVbyteReader<R: Read> {
+    src: R,
+    /// The number of integers not yet read from `src`
+    unread: u64,
+    block: [u32; BLOCK_LEN],
+    block_pos: usize,
+    block_len: usize,
+}
+
+impl<R: Read> StreamVbyteReader<R> {
+    /// Create a new stream reader decoding `count` integers from `src`
+    pub fn new(src: R, count: u64) -> StreamVbyteReader<R> {
+        StreamVbyteReader {
+            src,
+            unread: count,
+            block: [0; BLOCK_LEN],
+            block_pos: 0,
+            block_len: 0,
+        }
+    }
+
+    /// Decode the next integer of the stream, reading the next block from the underlying reader if required.
+    ///
+    /// Returns `None` once all `count` integers have been decoded. A stream ending before that returns
+    /// an [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) error.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> io::Result<Option<u32>> {
+        if self.block_pos == self.block_len {
+            if self.unread == 0 {
+                return Ok(None);
+            }
+            self.read_block()?;
+        }
+        let value = self.block[self.block_pos];
+        self.block_pos += 1;
+        Ok(Some(value))
+    }
+
+    /// The number of integers not yet decoded
+    pub fn remaining(&self) -> u64 {
+        self.unread + (self.block_len - self.block_pos) as u64
+    }
+
+    /// Returns the underlying reader
+    pub fn into_inner(self) -> R {
+        self.src
+    }
+
+    fn read_block(&mut self) -> io::Result<()> {
+        let block_len = self.unread.min(BLOCK_LEN as u64) as usize;
+        let mut buf = [0; max_compressedbytes(BLOCK_LEN)];
+        self.src.read_exact(&mut buf[..1])?;
+        let data_bytes = data_len(&buf[..1], block_len);
+        self.src.read_exact(&mut buf[1..1 + data_bytes])?;
+
+        let mut offset = 1;
+        for i in 0..block_len {
+            let code = (buf[0] >> (2 * i)) & 0b11;
+            self.block[i] = decode_value(code, &buf[offset..]);
+            offset += code_len(code);
+        }
+        self.unread -= block_len as u64;
+        self.block_pos = 0;
+        self.block_len = block_len;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writer_blocks_are_regular_encodings() {
+        let input = crate::test_input(10001u32);
+        let mut writer = StreamVbyteWriter::new(Vec::new());
+        for &value in &input {
+            writer.push(value).unwrap();
+        }
+        assert_eq!(writer.count(), input.len() as u64);
+        let out_bytes = writer.finish().unwrap();
+
+        let mut recovered = vec![0; input.len()];
+        let mut offset = 0;
+        for block in recovered.chunks_mut(BLOCK_LEN) {
+            offset += crate::decode(&out_bytes[offset..], block);
+        }
+        assert_eq!(offset, out_bytes.len());
+        assert_eq!(recovered, input);
+    }
+
+    /// A reader returning at most `chunk` bytes per read
+    struct Chunked<'a> {
+        input: &'a [u8],
+        chunk: usize,
+    }
+
+    impl Read for Chunked<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = self.chunk.min(buf.len()).min(self.input.len());
+            buf[..len].copy_from_slice(&self.input[..len]);
+            self.input = &self.input[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn reader_handles_small_reads_and_eof() {
+        for len in [0u32, 1, 3, 4, 8, 10001] {
+            let input = crate::test_input(len);
+            let mut writer = StreamVbyteWriter::new(Vec::new());
+            for &value in &input {
+                writer.push(value).unwrap();
+            }
+            let out_bytes = writer.finish().unwrap();
+
+            for chunk in [1, 2, 3, 4096] {
+                let src = Chunked {
+                    input: &out_bytes,
+                    chunk,
+                };
+                let mut reader = StreamVbyteReader::new(src, input.len() as u64);
+                for &expected in &input {
+                    assert_eq!(reader.next().unwrap(), Some(expected));
+                }
+                assert_eq!(reader.remaining(), 0);
+                assert_eq!(reader.next().unwrap(), None);
+                assert!(reader.into_inner().input.is_empty());
+            }
+
+            // a stream shorter than the expected count fails instead of returning `None`
+            let mut reader = StreamVbyteReader::new(&out_bytes[..], input.len() as u64 + 1);
+            let err = (0..=input.len())
+                .map(|_| reader.next())
+                .collect::<io::Result<Vec<_>>>()
+                .unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Conversions between the different encodings without a manual decode/encode round-trip.
+
+use alloc::{vec, vec::Vec};
+
+use crate::reference::encode_values;
+use crate::{
+    decode, decode_0124, decode_delta_iter, decode_iter, encode_0124_to_buf, encode_to_buf,
+    max_compressedbytes,
+};
+
+/// Decode `count` integers from `input` into a scratch buffer and re-encode them
+fn transcode<D, E>(input: &[u8], count: usize, decode: D, encode: E) -> Vec<u8>
+where
+    D: FnOnce(&[u8], &mut [u32]),
+    E: FnOnce(&[u32], &mut [u8]) -> usize,
+{
+    let mut scratch = vec![0; count];
+    decode(input, &mut scratch);
+    let mut output = vec![0; max_compressedbytes(count)];
+    let bytes_written = encode(&scratch, &mut output);
+    output.truncate(bytes_written);
+    output
+}
+
+/// Convert `count` integers encoded with [`encode`](crate::encode) into the 0124 encoding produced by
+/// [`encode_0124`](crate::encode_0124) which stores values in 0, 1, 2 or 4 bytes.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode,plain_from_0124,plain_to_0124};
+/// let input = vec![0,0,0,1,0,0,5123,0];
+/// let plain = encode(&input);
+/// let zero124 = plain_to_0124(&plain,input.len());
+/// assert!(zero124.len() < plain.len());
+/// assert_eq!(plain_from_0124(&zero124,input.len()),plain);
+/// ```
+/// # Return
+///
+/// Returns the 0124 encoded output as a byte buffer
+///
+pub fn plain_to_0124(input: &[u8], count: usize) -> Vec<u8> {
+    transcode(
+        input,
+        count,
+        |input, scratch| {
+            decode(input, scratch);
+        },
+        // unwrap ok as output holds max_compressedbytes bytes
+        |scratch, output| encode_0124_to_buf(scratch, output).unwrap(),
+    )
+}
+
+/// Convert `count` integers in the 0124 encoding back into the default encoding produced by [`encode`](crate::encode).
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode,encode,plain_from_0124,plain_to_0124};
+/// let input = vec![0,0,0,1,0,0,5123,0];
+/// let zero124 = plain_to_0124(&encode(&input),input.len());
+/// let mut recovered = vec![0;input.len()];
+/// decode(&plain_from_0124(&zero124,input.len()),&mut recovered);
+/// assert_eq!(recovered,input);
+/// ```
+/// # Return
+///
+/// Returns the encoded output as a byte buffer
+///
+pub fn plain_from_0124(input: &[u8], count: usize) -> Vec<u8> {
+    transcode(
+        input,
+        count,
+        |input, scratch| {
+            decode_0124(input, scratch);
+        },
+        // unwrap ok as output holds max_compressedbytes bytes
+        |scratch, output| encode_to_buf(scratch, output).unwrap(),
+    )
+}
+
+/// Convert `count` integers encoded with [`encode`](crate::encode) into the delta encoding produced by
+/// [`encode_delta`](crate::encode_delta), decoding and re-encoding a single block of four integers at a time.
+///
+/// The encoded integers **MUST** be non decreasing and not smaller than `initial`, otherwise the output
+/// stores wrapped around differences. Debug builds panic on such a violation.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode,encode_delta,transcode_to_delta};
+/// let input = vec![1,2,44,5123,5124,5340];
+/// assert_eq!(transcode_to_delta(&encode(&input),input.len(),1),encode_delta(&input,1));
+/// ```
+/// # Panics
+///
+/// Panics if `input` is too short to hold `count` integers
+///
+/// # Return
+///
+/// Returns the delta encoded output as a byte buffer
+///
+pub fn transcode_to_delta(input: &[u8], count: usize, initial: u32) -> Vec<u8> {
+    let deltas = decode_iter(input, count)
+        .enumerate()
+        .scan(initial, |prev, (i, value)| {
+            debug_assert!(
+                value >= *prev,
+                "value {} at index {} is smaller than its predecessor {}",
+                value,
+                i,
+                prev
+            );
+            let delta = value.wrapping_sub(*prev);
+            *prev = value;
+            Some(delta)
+        });
+    encode_values(deltas, count)
+}
+
+/// Convert `count` integers encoded with [`encode_delta`](crate::encode_delta) into the encoding produced by
+/// [`encode`](crate::encode), decoding and re-encoding a single block of four integers at a time.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode,encode_delta,transcode_to_plain};
+/// let input = vec![1,2,44,5123,5124,5340];
+/// assert_eq!(transcode_to_plain(&encode_delta(&input,1),input.len(),1),encode(&input));
+/// ```
+/// # Panics
+///
+/// Panics if `input` is too short to hold `count` integers
+///
+/// # Return
+///
+/// Returns the encoded output as a byte buffer
+///
+pub fn transcode_to_plain(input: &[u8], count: usize, initial: u32) -> Vec<u8> {
+    encode_values(decode_delta_iter(input, count, initial), count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transcode_0124_roundtrip() {
+        for len in [0usize, 1, 5, 1000, 10001] {
+            let input: Vec<u32> = (0..len as u32)
+                .map(|v| if v % 3 == 0 { v * 11 } else { 0 })
+                .collect();
+            let plain = crate::encode(&input);
+            let zero124 = plain_to_0124(&plain, len);
+            assert!(zero124.len() <= plain.len());
+
+            let mut recovered = vec![0; len];
+            assert_eq!(decode_0124(&zero124, &mut recovered), zero124.len());
+            assert_eq!(recovered, input);
+            assert_eq!(plain_from_0124(&zero124, len), plain);
+        }
+    }
+
+    #[test]
+    fn transcode_delta_roundtrip() {
+        for len in [0u32, 1, 5, 1000] {
+            let input: Vec<u32> = (0..len).map(|v| 3 + v * 1013 + (v % 7) * 100).collect();
+            let plain = crate::encode(&input);
+            let delta = transcode_to_delta(&plain, input.len(), 3);
+            assert_eq!(delta, crate::encode_delta(&input, 3));
+            assert_eq!(transcode_to_plain(&delta, input.len(), 3), plain);
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "smaller than its predecessor")]
+    fn transcode_to_delta_rejects_unsorted_input() {
+        transcode_to_delta(&crate::encode(&[5, 7, 6]), 3, 0);
+    }
+}
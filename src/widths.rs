@@ -0,0 +1,79 @@
+//! Encoding with byte widths supplied by the caller instead of derived from every value.
+
+use alloc::{vec, vec::Vec};
+
+use crate::layout::{control_bytes_for, MAX_BYTES_PER_INT};
+use crate::StreamVbyteError;
+
+/// Encode a sequence of u32 integers storing `input[i]` in `widths[i]` data bytes.
+///
+/// Callers that know the magnitude profile of their data, e.g. only 2 byte values, can skip the
+/// derivation of the length codes. Every width is only checked to hold its value. A width larger
+/// than required is stored as is, so the output is identical to [`encode`](crate::encode) only if all widths are
+/// minimal, but it is always decoded correctly by [`decode`](crate::decode).
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_to_vec,encode,encode_with_widths};
+/// let input = vec![1,2,44,5123,43,534];
+/// let out_bytes = encode_with_widths(&input,&[2;6]).unwrap();
+/// assert_eq!(out_bytes.len(),14);
+/// assert_eq!(decode_to_vec(&out_bytes,6),input);
+/// assert_eq!(encode_with_widths(&input,&[1,1,1,2,1,2]).unwrap(),encode(&input));
+/// assert!(encode_with_widths(&input,&[1;6]).is_err());
+/// ```
+/// # Panics
+///
+/// Panics if `widths` does not hold a width per integer of `input`
+///
+/// # Return
+///
+/// Returns the encoded output as a byte buffer or [`StreamVbyteError::InsufficientWidth`] with the index of
+/// the first width outside of `1..=4` or too small for its value
+///
+pub fn encode_with_widths(input: &[u32], widths: &[u8]) -> Result<Vec<u8>, StreamVbyteError> {
+    assert_eq!(
+        input.len(),
+        widths.len(),
+        "widths must hold a width per integer"
+    );
+    let mut out = vec![0; control_bytes_for(input.len())];
+    out.reserve(widths.iter().map(|&w| w as usize).sum());
+    for (index, (&value, &width)) in input.iter().zip(widths).enumerate() {
+        let len = width as usize;
+        if len == 0 || len > MAX_BYTES_PER_INT || u64::from(value) >> (8 * len) != 0 {
+            return Err(StreamVbyteError::InsufficientWidth { index, width });
+        }
+        out[index / 4] |= (width - 1) << (2 * (index % 4));
+        out.extend_from_slice(&value.to_le_bytes()[..len]);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widths_roundtrip_and_are_validated() {
+        let input = crate::test_input(1001u32);
+        let minimal: Vec<u8> = input
+            .iter()
+            .map(|&v| crate::reference::value_code(v) + 1)
+            .collect();
+        assert_eq!(
+            encode_with_widths(&input, &minimal).unwrap(),
+            crate::encode(&input)
+        );
+        let out_bytes = encode_with_widths(&input, &vec![4; input.len()]).unwrap();
+        assert_eq!(crate::decode_to_vec(&out_bytes, input.len()), input);
+
+        for (value, width) in [(0, 0), (1, 5), (0x100, 1), (0x10000, 2), (0x1000000, 3)] {
+            assert!(matches!(
+                encode_with_widths(&[7, value], &[1, width]),
+                Err(StreamVbyteError::InsufficientWidth { index: 1, width: w }) if w == width
+            ));
+        }
+    }
+}
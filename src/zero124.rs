@@ -0,0 +1,153 @@
+//! The 0124 encoding which stores each value in 0, 1, 2 or 4 data bytes.
+//!
+//! Zeros occupy no data bytes at all, which makes the encoding considerably smaller for sequences
+//! dominated by zeros. The control section uses the same 2-bit codes as the default encoding, so
+//! a 0124 encoded stream is **not** interchangeable with [`encode`](crate::encode) and
+//! [`decode`](crate::decode).
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use crate::{ffi, try_max_compressedbytes, StreamVbyteError};
+
+/// Encode a sequence of u32 integers into the 0124 vbyte representation.
+/// Internally a buffer of length [`max_compressedbytes`](crate::max_compressedbytes) is allocated to store the compressed result.
+///
+/// The output **MUST** be decoded with [`decode_0124`], decoding it with [`decode`](crate::decode)
+/// returns garbage.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode,encode_0124};
+/// let input = vec![0,0,0,1,0,0,5123,0];
+/// let out_bytes: Vec<u8> = encode_0124(&input);
+/// assert!(out_bytes.len() < encode(&input).len());
+/// ```
+/// # Panics
+///
+/// Panics if `input` holds more than `u32::MAX` integers
+///
+/// # Return
+///
+/// Returns the encoded output as a byte buffer
+///
+#[cfg(feature = "alloc")]
+pub fn encode_0124(input: &[u32]) -> Vec<u8> {
+    let output_bytes_req =
+        try_max_compressedbytes(input.len()).expect("at most u32::MAX integers can be encoded");
+    let mut buf = vec![0; output_bytes_req];
+    // SAFETY: unwrap ok as we compute required max bytes beforehand
+    let bytes_written = encode_0124_to_buf(input, &mut buf).unwrap();
+    buf.truncate(bytes_written);
+    buf
+}
+
+/// Encode a sequence of u32 integers into the 0124 vbyte representation into an existing buffer `output`.
+///
+/// Required: output buf is at least [`max_compressedbytes`](crate::max_compressedbytes) long.
+///
+/// The output **MUST** be decoded with [`decode_0124`], decoding it with [`decode`](crate::decode)
+/// returns garbage.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{max_compressedbytes,encode_0124_to_buf};
+/// let input = vec![0,0,0,1,0,0,5123,0];
+/// let max_bytes = max_compressedbytes(input.len());
+/// let mut out_buf = vec![0;max_bytes];
+/// let bytes_written = encode_0124_to_buf(&input,&mut out_buf);
+/// assert_eq!(bytes_written.unwrap(),5);
+/// ```
+/// # Return
+///
+/// Returns the number of bytes written to output during encoding or [`StreamVbyteError::LengthTooLarge`]
+/// if `input` holds more than `u32::MAX` integers
+///
+pub fn encode_0124_to_buf(input: &[u32], output: &mut [u8]) -> Result<usize, StreamVbyteError> {
+    let output_bytes_req = try_max_compressedbytes(input.len())?;
+    if output.len() < output_bytes_req {
+        return Err(StreamVbyteError::OutbufOverflow(
+            output.len(),
+            output_bytes_req,
+        ));
+    }
+    // SAFETY: output buf is as long as max compressed size
+    unsafe {
+        Ok(
+            ffi::streamvbyte_encode_0124(input.as_ptr(), input.len() as u32, output.as_mut_ptr())
+                as usize,
+        )
+    }
+}
+
+/// Decode a sequence of u32 integers encoded by [`encode_0124`] into an existing buffer `output`.
+///
+/// Only streams produced by [`encode_0124`] or [`encode_0124_to_buf`] can be decoded, streams
+/// produced by [`encode`](crate::encode) **MUST** be decoded with [`decode`](crate::decode).
+///
+/// # Arguments
+///
+/// * `input` - The input sequence of 0124 vbyte encoding (u8s)
+/// * `output` - The output buf to store the recovered u32 integers. **MUST** be the same size as the original input sequence
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{encode_0124,decode_0124};
+/// let input = vec![0,0,0,1,0,0,5123,0];
+/// let out_buf = encode_0124(&input);
+/// let mut recovered = vec![0;8];
+/// let bytes_read = decode_0124(&out_buf,&mut recovered);
+/// assert_eq!(bytes_read,out_buf.len());
+/// assert_eq!(recovered,input);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `output` holds more than `u32::MAX` integers
+///
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding
+///
+pub fn decode_0124(input: &[u8], output: &mut [u32]) -> usize {
+    assert!(
+        output.len() <= u32::MAX as usize,
+        "at most u32::MAX integers can be decoded"
+    );
+    unsafe {
+        ffi::streamvbyte_decode_0124(input.as_ptr(), output.as_mut_ptr(), output.len() as u32)
+            as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_0124_sparse_roundtrip() {
+        for len in [0usize, 1, 3, 4, 1000, 10001] {
+            let input: Vec<u32> = (0..len as u32)
+                .map(|v| match v % 16 {
+                    0 => v.wrapping_mul(2654435761),
+                    1 => v % 300,
+                    _ => 0,
+                })
+                .collect();
+            let out_bytes = encode_0124(&input);
+            let plain = crate::encode(&input);
+            assert!(out_bytes.len() <= plain.len());
+            if len >= 1000 {
+                // zeros take no data bytes, roughly halving the size
+                assert!(out_bytes.len() * 2 < plain.len());
+            }
+
+            let mut recovered = vec![0; len];
+            assert_eq!(decode_0124(&out_bytes, &mut recovered), out_bytes.len());
+            assert_eq!(recovered, input);
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Signed integer support by zigzag mapping i32 values to u32 before encoding.
+//!
+//! Zigzag encoding maps `0, -1, 1, -2, 2, ...` to `0, 1, 2, 3, 4, ...` so values of small magnitude
+//! stay small regardless of their sign. The mapping is implemented here instead of using the C
+//! helpers as those rely on signed overflow for values of large magnitude.
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+
+use crate::decode;
+#[cfg(feature = "alloc")]
+use crate::{encode, encode_to_buf, StreamVbyteError};
+
+/// Map a signed integer to an unsigned integer of similar magnitude
+#[cfg(feature = "alloc")]
+pub(crate) fn zigzag_encode_value(value: i32) -> u32 {
+    (value as u32).wrapping_shl(1) ^ ((value >> 31) as u32)
+}
+
+/// Inverse of [`zigzag_encode_value`]
+pub(crate) fn zigzag_decode_value(value: u32) -> i32 {
+    ((value >> 1) ^ (value & 1).wrapping_neg()) as i32
+}
+
+#[cfg(feature = "alloc")]
+fn zigzag_encode_values(input: &[i32]) -> Vec<u32> {
+    input.iter().map(|&v| zigzag_encode_value(v)).collect()
+}
+
+/// Encode a sequence of i32 integers into a vbyte encoded byte representation using zigzag encoding.
+/// Internally a buffer of length [`max_compressedbytes`](crate::max_compressedbytes) is allocated to store the compressed result.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::encode_zigzag;
+/// let out_bytes: Vec<u8> = encode_zigzag(&[1,-2,44,-5123,43,534]);
+/// ```
+/// # Return
+///
+/// Returns the encoded output as a byte buffer
+///
+#[cfg(feature = "alloc")]
+pub fn encode_zigzag(input: &[i32]) -> Vec<u8> {
+    encode(&zigzag_encode_values(input))
+}
+
+/// Encode a sequence of i32 integers into a vbyte encoded byte representation using zigzag encoding into an existing buffer `output`.
+///
+/// Required: output buf is at least [`max_compressedbytes`](crate::max_compressedbytes) long.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{max_compressedbytes,encode_zigzag_to_buf};
+/// let input = vec![1,-2,44,-5123,43,534];
+/// let max_bytes = max_compressedbytes(input.len());
+/// let mut out_buf = vec![0;max_bytes];
+/// let bytes_written = encode_zigzag_to_buf(&input,&mut out_buf);
+/// assert_eq!(bytes_written.unwrap(),10);
+/// ```
+/// # Return
+///
+/// Returns the number of bytes written to output during encoding
+///
+#[cfg(feature = "alloc")]
+pub fn encode_zigzag_to_buf(input: &[i32], output: &mut [u8]) -> Result<usize, StreamVbyteError> {
+    encode_to_buf(&zigzag_encode_values(input), output)
+}
+
+/// Decode a sequence of i32 integers encoded with [`encode_zigzag`] into an existing buffer `output`.
+///
+/// # Arguments
+///
+/// * `input` - The input sequence of vbyte encoding (u8s)
+/// * `output` - The output buf to store the recovered i32 integers. **MUST** be the same size as the original input sequence
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_zigzag,encode_zigzag};
+/// let out_buf = encode_zigzag(&[1,-2,44,-5123,43,534]);
+/// let mut recovered = vec![0;6];
+/// let bytes_read = decode_zigzag(&out_buf,&mut recovered);
+/// assert_eq!(bytes_read,out_buf.len());
+/// assert_eq!(recovered,vec![1,-2,44,-5123,43,534]);
+/// ```
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding
+///
+#[cfg(feature = "alloc")]
+pub fn decode_zigzag(input: &[u8], output: &mut [i32]) -> usize {
+    let mut values = vec![0; output.len()];
+    let bytes_read = decode(input, &mut values);
+    for (out, value) in output.iter_mut().zip(values) {
+        *out = zigzag_decode_value(value);
+    }
+    bytes_read
+}
+
+/// Encode a nearly sorted sequence of u32 integers as the zigzag mapped differences between successive values.
+///
+/// Unlike [`encode_delta`](crate::encode_delta), which requires a non decreasing input, the differences may be
+/// negative, so sequences which mostly increase but occasionally decrease by a small amount still encode into
+/// mostly single byte values. The first value is stored as its difference to `0`. Differences are computed
+/// with wrapping arithmetic, so any input is restored by [`decode_delta_zigzag`].
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_delta_zigzag,encode_delta,encode_delta_zigzag};
+/// let input = vec![1000,1003,1001,1001,1010,1008];
+/// let out_bytes = encode_delta_zigzag(&input);
+/// assert_eq!(out_bytes.len(),9);
+/// let mut recovered = vec![0;6];
+/// assert_eq!(decode_delta_zigzag(&out_bytes,&mut recovered),out_bytes.len());
+/// assert_eq!(recovered,input);
+/// ```
+/// # Return
+///
+/// Returns the encoded output as a byte buffer
+///
+#[cfg(feature = "alloc")]
+pub fn encode_delta_zigzag(input: &[u32]) -> Vec<u8> {
+    let mut prev = 0u32;
+    let gaps: Vec<u32> = input
+        .iter()
+        .map(|&value| {
+            let gap = value.wrapping_sub(prev) as i32;
+            prev = value;
+            zigzag_encode_value(gap)
+        })
+        .collect();
+    encode(&gaps)
+}
+
+/// Decode a sequence of u32 integers encoded with [`encode_delta_zigzag`] into an existing buffer `output`.
+///
+/// # Arguments
+///
+/// * `input` - The input sequence of vbyte encoding (u8s)
+/// * `output` - The output buf to store the recovered u32 integers. **MUST** be the same size as the original input sequence
+///
+/// # Return
+///
+/// Returns the number of bytes processed from input during decoding
+///
+pub fn decode_delta_zigzag(input: &[u8], output: &mut [u32]) -> usize {
+    let bytes_read = decode(input, output);
+    undo_delta_zigzag(output);
+    bytes_read
+}
+
+/// Turn the zigzag mapped differences decoded into `output` back into the original values
+pub(crate) fn undo_delta_zigzag(output: &mut [u32]) {
+    let mut prev = 0u32;
+    for value in output.iter_mut() {
+        prev = prev.wrapping_add(zigzag_decode_value(*value) as u32);
+        *value = prev;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zigzag_mapping() {
+        let mapped: Vec<u32> = [0, -1, 1, -2, 2]
+            .iter()
+            .map(|&v| zigzag_encode_value(v))
+            .collect();
+        assert_eq!(mapped, vec![0, 1, 2, 3, 4]);
+        assert_eq!(zigzag_encode_value(i32::MAX), u32::MAX - 1);
+        assert_eq!(zigzag_encode_value(i32::MIN), u32::MAX);
+    }
+
+    #[test]
+    fn encode_decode_zigzag_roundtrip() {
+        let mut input: Vec<i32> = vec![0, -1, 1, i32::MIN, i32::MAX, i32::MIN + 1, i32::MAX - 1];
+        input.extend((0..10000).map(|v: i32| v.wrapping_mul(-1640531527) >> (v % 32)));
+        let output_buf = encode_zigzag(&input);
+        let mut out_buf = vec![0; crate::max_compressedbytes(input.len())];
+        assert_eq!(
+            encode_zigzag_to_buf(&input, &mut out_buf).unwrap(),
+            output_buf.len()
+        );
+        let mut recovered = vec![0; input.len()];
+        assert_eq!(decode_zigzag(&output_buf, &mut recovered), output_buf.len());
+        assert_eq!(recovered, input);
+    }
+
+    #[test]
+    fn delta_zigzag_handles_repeats_and_backward_jumps() {
+        let input = vec![5, 5, 5, 3, 7, 7, u32::MAX, 0, 2, 1_000_000, 10, 10];
+        let out_bytes = encode_delta_zigzag(&input);
+        let mut gaps = vec![0; input.len()];
+        decode(&out_bytes, &mut gaps);
+        assert_eq!(&gaps[..6], &[10, 0, 0, 3, 8, 0]);
+
+        let mut recovered = vec![0; input.len()];
+        assert_eq!(
+            decode_delta_zigzag(&out_bytes, &mut recovered),
+            out_bytes.len()
+        );
+        assert_eq!(recovered, input);
+        assert!(encode_delta_zigzag(&[]).is_empty());
+    }
+}
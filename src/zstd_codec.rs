@@ -0,0 +1,108 @@
+//! vbyte encoding followed by a zstd pass over the encoded bytes.
+//!
+//! The output is laid out as `[count: u32 little endian][zstd compressed payload]`.
+
+use std::io::{self, Read};
+
+use crate::layout::{encoded_size, PLAIN_CODE_LENS};
+use crate::{encode, max_compressedbytes, Codec, StreamVbyteError};
+
+/// Encode a sequence of u32 integers with [`encode`] and compress the result with zstd.
+///
+/// The vbyte pass removes most leading zero bytes and groups the length codes, which usually makes the
+/// zstd pass more effective than on the raw integers.
+///
+/// # Arguments
+///
+/// * `input` - The input sequence of u32 integers. Can be at most `u32::MAX` integers
+/// * `level` - The zstd compression level
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_zstd,encode_zstd};
+/// let input: Vec<u32> = (0..1000).map(|v| v % 7).collect();
+/// let out_bytes = encode_zstd(&input,3);
+/// assert_eq!(decode_zstd(&out_bytes).unwrap(),input);
+/// ```
+/// # Return
+///
+/// Returns the compressed output as a byte buffer
+///
+pub fn encode_zstd(input: &[u32], level: i32) -> Vec<u8> {
+    let payload = encode(input);
+    let mut out = (input.len() as u32).to_le_bytes().to_vec();
+    // unwrap ok as compressing from and into memory cannot fail
+    out.extend(zstd::encode_all(&payload[..], level).unwrap());
+    out
+}
+
+/// Decode a buffer produced by [`encode_zstd`].
+///
+/// Decompression stops after the largest payload the stored count can be encoded in, so a small
+/// corrupted or malicious input cannot expand into an arbitrarily large buffer.
+///
+/// # Examples
+///
+/// ```
+/// use streamvbyte::{decode_zstd,encode_zstd};
+/// let out_bytes = encode_zstd(&[1,2,44,5123,43,534],3);
+/// assert_eq!(decode_zstd(&out_bytes).unwrap(),vec![1,2,44,5123,43,534]);
+/// assert!(decode_zstd(&out_bytes[..out_bytes.len()-1]).is_err());
+/// ```
+/// # Return
+///
+/// Returns the decoded integers
+///
+pub fn decode_zstd(input: &[u8]) -> Result<Vec<u32>, StreamVbyteError> {
+    if input.len() < 4 {
+        return Err(StreamVbyteError::TruncatedStream(input.len(), 4));
+    }
+    let count = u32::from_le_bytes([input[0], input[1], input[2], input[3]]) as usize;
+    // the encoding of `count` integers is bounded, so one more byte than that is enough to reject a larger payload
+    let limit = max_compressedbytes(count);
+    let mut payload = Vec::new();
+    zstd::Decoder::new(&input[4..])
+        .and_then(|decoder| decoder.take(limit as u64 + 1).read_to_end(&mut payload))
+        .map_err(StreamVbyteError::Zstd)?;
+    if payload.len() > limit {
+        return Err(StreamVbyteError::Zstd(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "decompressed payload exceeds the encoding of the stored count",
+        )));
+    }
+    // validated before allocating, as the count is untrusted
+    encoded_size(&payload, count, &PLAIN_CODE_LENS)?;
+    let mut output = vec![0; count];
+    Codec::Plain.decode_checked(&payload, &mut output, 0)?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zstd_roundtrip() {
+        for len in [0u32, 1, 3, 1000, 10001] {
+            let input: Vec<u32> = (0..len).map(|v| (v * 31) % 1000).collect();
+            let out_bytes = encode_zstd(&input, 3);
+            assert_eq!(decode_zstd(&out_bytes).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn zstd_rejects_oversized_payload() {
+        let mut out_bytes = encode_zstd(&[0; 10000], 3);
+        out_bytes[..4].copy_from_slice(&10u32.to_le_bytes());
+        assert!(matches!(
+            decode_zstd(&out_bytes),
+            Err(StreamVbyteError::Zstd(..))
+        ));
+        out_bytes[..4].copy_from_slice(&20000u32.to_le_bytes());
+        assert!(matches!(
+            decode_zstd(&out_bytes),
+            Err(StreamVbyteError::TruncatedStream(..))
+        ));
+    }
+}